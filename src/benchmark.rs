@@ -0,0 +1,59 @@
+//! BENCHMARK DEL RUIDO
+//! `cargo run --release -- --bench` renderiza la estrella sin abrir ventana, primero con
+//! el ruido exacto y luego con los volúmenes horneados, cada uno sombreando fragmento
+//! por fragmento y en tramos de 4 (ruido en lotes SIMD), e imprime el tiempo de
+//! horneado y el tiempo medio por cuadro de cada camino
+
+use std::time::{Duration, Instant};
+use crate::framebuffer::Framebuffer;
+use crate::uniforms::Uniforms;
+use crate::renderer::{render, render_spans};
+use crate::star_shader::StarShader;
+use crate::sunspots::SunspotSystem;
+use crate::vertex::Vertex;
+use crate::Scene;
+
+/// Cuadros por camino (cada uno en un instante distinto de la animación)
+const FRAMES: u32 = 20;
+
+/// Tiempo medio por cuadro de `shader` sobre la misma secuencia de instantes
+/// (`spans`: sombreando tramos de 4 fragmentos con el ruido en lotes)
+fn time_frames(framebuffer: &mut Framebuffer, uniforms: &mut Uniforms, shader: &StarShader, vertices: &[Vertex], sunspots: &SunspotSystem, spans: bool) -> Duration {
+    let start = Instant::now();
+    for frame in 0..FRAMES {
+        uniforms.time = frame as f32 * 0.25;
+        uniforms.sunspots = sunspots.active_spots(uniforms.time, &uniforms.differential_rotation);
+        framebuffer.clear();
+        if spans {
+            render_spans(framebuffer, uniforms, shader, vertices);
+        } else {
+            render(framebuffer, uniforms, shader, vertices);
+        }
+    }
+    start.elapsed() / FRAMES
+}
+
+/// Compara el ruido exacto con el horneado a `resolution`³ vóxeles, en la misma escena
+/// inicial que la ventana
+pub fn run(star_seed: u64, sunspot_seed: u64, resolution: u32) {
+    let Scene { mut framebuffer, mut uniforms, sphere_vertices, sphere_indices, .. } = Scene::new();
+    let vertices: Vec<Vertex> = sphere_indices.iter().map(|&index| sphere_vertices[index].clone()).collect();
+    let sunspots = SunspotSystem::new(sunspot_seed);
+
+    let mut shader = StarShader::new(star_seed);
+    let exact = time_frames(&mut framebuffer, &mut uniforms, &shader, &vertices, &sunspots, false);
+    let exact_spans = time_frames(&mut framebuffer, &mut uniforms, &shader, &vertices, &sunspots, true);
+
+    let start = Instant::now();
+    shader.bake(resolution);
+    let bake = start.elapsed();
+    let baked = time_frames(&mut framebuffer, &mut uniforms, &shader, &vertices, &sunspots, false);
+    let baked_spans = time_frames(&mut framebuffer, &mut uniforms, &shader, &vertices, &sunspots, true);
+
+    let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+    println!("📊 Benchmark del ruido ({} cuadros de {}x{})", FRAMES, framebuffer.width, framebuffer.height);
+    println!("                 por fragmento   tramos de 4 (SIMD)");
+    println!("   Exacto:   {:>10.2} ms {:>14.2} ms ({:.2}x)", ms(exact), ms(exact_spans), ms(exact) / ms(exact_spans));
+    println!("   Horneado: {:>10.2} ms {:>14.2} ms ({:.2}x)", ms(baked), ms(baked_spans), ms(baked) / ms(baked_spans));
+    println!("   Horneado en {:.2} s ({}³ vóxeles); aceleración del horneado: {:.2}x", bake.as_secs_f64(), resolution, ms(exact) / ms(baked));
+}
@@ -0,0 +1,188 @@
+//! COLOR DE CUERPO NEGRO
+//! Convierte una temperatura en Kelvin al color (RGB lineal) que emite un cuerpo negro:
+//! se integra la ley de Planck contra una aproximación analítica de las funciones de igualación CIE 1931
+//! para obtener XYZ y luego se pasa a sRGB lineal
+//!
+//! La integral es cara, así que se precalcula una tabla de temperaturas una sola vez
+
+use crate::math::Vec3;
+use std::sync::OnceLock;
+
+/// Temperatura efectiva del Sol
+pub const SUN_TEMPERATURE: f32 = 5772.0;
+
+/// Rango de la tabla precalculada
+pub const MIN_TEMPERATURE: f32 = 1000.0;
+pub const MAX_TEMPERATURE: f32 = 40000.0;
+const TABLE_SIZE: usize = 512;
+
+/// Muestreo del espectro visible (nm)
+const WAVELENGTH_START: f64 = 380.0;
+const WAVELENGTH_END: f64 = 780.0;
+const WAVELENGTH_STEP: f64 = 5.0;
+
+/// Lóbulo gaussiano asimétrico del ajuste de Wyman, Sloan y Shirley (2013)
+fn lobe(wavelength: f64, mean: f64, sigma_low: f64, sigma_high: f64) -> f64 {
+    let sigma = if wavelength < mean { sigma_low } else { sigma_high };
+    let t = (wavelength - mean) / sigma;
+    (-0.5 * t * t).exp()
+}
+
+/// Aproximación analítica de las funciones de igualación de color CIE 1931 (observador
+/// de 2°) en `wavelength` nm: suma de lóbulos gaussianos ajustada a las tablas de la CIE
+fn cie_1931(wavelength: f64) -> (f64, f64, f64) {
+    let x = 1.056 * lobe(wavelength, 599.8, 37.9, 31.0)
+        + 0.362 * lobe(wavelength, 442.0, 16.0, 26.7)
+        - 0.065 * lobe(wavelength, 501.1, 20.4, 26.2);
+    let y = 0.821 * lobe(wavelength, 568.8, 46.9, 40.5)
+        + 0.286 * lobe(wavelength, 530.9, 16.3, 31.1);
+    let z = 1.217 * lobe(wavelength, 437.0, 11.8, 36.0)
+        + 0.681 * lobe(wavelength, 459.0, 26.0, 13.8);
+    (x, y, z)
+}
+
+/// Ley de Planck: radiancia espectral de un cuerpo negro (la escala absoluta no importa)
+fn planck(wavelength_nm: f64, kelvin: f64) -> f64 {
+    const H: f64 = 6.626_070_15e-34;  // Constante de Planck
+    const C: f64 = 2.997_924_58e8;    // Velocidad de la luz
+    const K: f64 = 1.380_649e-23;     // Constante de Boltzmann
+
+    let wavelength = wavelength_nm * 1e-9;
+    let numerator = 2.0 * H * C * C / wavelength.powi(5);
+    numerator / ((H * C / (wavelength * K * kelvin)).exp() - 1.0)
+}
+
+/// Integra el espectro de Planck contra las funciones CIE y retorna RGB lineal
+/// normalizado (el canal más brillante vale 1: solo importa el tono)
+pub fn blackbody_color_exact(kelvin: f32) -> Vec3 {
+    let kelvin = kelvin.max(1.0) as f64;
+    let (mut x, mut y, mut z) = (0.0, 0.0, 0.0);
+
+    let mut wavelength = WAVELENGTH_START;
+    while wavelength <= WAVELENGTH_END {
+        let radiance = planck(wavelength, kelvin);
+        let (cx, cy, cz) = cie_1931(wavelength);
+        x += radiance * cx;
+        y += radiance * cy;
+        z += radiance * cz;
+        wavelength += WAVELENGTH_STEP;
+    }
+
+    // XYZ -> sRGB lineal (primarios Rec. 709, blanco D65)
+    let r = 3.2406 * x - 1.5372 * y - 0.4986 * z;
+    let g = -0.9689 * x + 1.8758 * y + 0.0415 * z;
+    let b = 0.0557 * x - 0.2040 * y + 1.0570 * z;
+
+    // Los colores fuera de gamut (estrellas muy frías o muy calientes) se recortan
+    let rgb = Vec3::new(r.max(0.0) as f32, g.max(0.0) as f32, b.max(0.0) as f32);
+    let max = rgb.x.max(rgb.y).max(rgb.z);
+    if max > 0.0 { rgb / max } else { rgb }
+}
+
+/// Posición en la tabla: se muestrea uniformemente en 1/T (mireds), que es
+/// aproximadamente uniforme en percepción del color
+fn table_coordinate(kelvin: f32) -> f32 {
+    let inverse = 1.0 / kelvin.clamp(MIN_TEMPERATURE, MAX_TEMPERATURE);
+    let inverse_min = 1.0 / MAX_TEMPERATURE;
+    let inverse_max = 1.0 / MIN_TEMPERATURE;
+    (inverse_max - inverse) / (inverse_max - inverse_min) * (TABLE_SIZE - 1) as f32
+}
+
+fn table() -> &'static [Vec3] {
+    static TABLE: OnceLock<Vec<Vec3>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let inverse_min = 1.0 / MAX_TEMPERATURE;
+        let inverse_max = 1.0 / MIN_TEMPERATURE;
+        (0..TABLE_SIZE)
+            .map(|i| {
+                let t = i as f32 / (TABLE_SIZE - 1) as f32;
+                let inverse = inverse_max + (inverse_min - inverse_max) * t;
+                blackbody_color_exact(1.0 / inverse)
+            })
+            .collect()
+    })
+}
+
+/// Color de cuerpo negro (RGB lineal normalizado) usando la tabla precalculada
+pub fn blackbody_color(kelvin: f32) -> Vec3 {
+    let table = table();
+    let coordinate = table_coordinate(kelvin);
+    let index = (coordinate.floor() as usize).min(TABLE_SIZE - 2);
+    let t = coordinate - index as f32;
+    table[index].lerp(table[index + 1], t)
+}
+
+/// Brillo relativo por Stefan-Boltzmann: la emisión total crece con T⁴
+pub fn relative_luminosity(kelvin: f32, reference_kelvin: f32) -> f32 {
+    (kelvin / reference_kelvin).powi(4)
+}
+
+/// Codificación sRGB (gamma) de un canal lineal en [0, 1] para mostrarlo en pantalla
+pub fn linear_to_srgb(value: f32) -> f32 {
+    let value = value.clamp(0.0, 1.0);
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Inversa de `linear_to_srgb`: decodifica un canal sRGB en [0, 1] a lineal
+pub fn srgb_to_linear(value: f32) -> f32 {
+    let value = value.clamp(0.0, 1.0);
+    if value <= 0.040_45 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sun_is_nearly_white_and_slightly_warm() {
+        let sun = blackbody_color(SUN_TEMPERATURE);
+        assert!((sun.x - 1.0).abs() < 1e-3);
+        assert!(sun.x >= sun.y && sun.y >= sun.z);
+        assert!(sun.z > 0.75, "el Sol no debe verse naranja: {:?}", sun);
+    }
+
+    #[test]
+    fn cool_stars_are_red_and_hot_stars_are_blue() {
+        let red_dwarf = blackbody_color(3000.0);
+        assert!(red_dwarf.x > red_dwarf.y && red_dwarf.y > red_dwarf.z);
+        assert!(red_dwarf.z < 0.4);
+
+        let blue_giant = blackbody_color(25000.0);
+        assert!(blue_giant.z > blue_giant.x);
+    }
+
+    #[test]
+    fn blue_fraction_grows_with_temperature() {
+        let mut previous = 0.0;
+        for kelvin in [2500.0, 4000.0, 5000.0, 8000.0, 15000.0, 30000.0] {
+            let color = blackbody_color(kelvin);
+            let blue_fraction = color.z / (color.x + color.y + color.z);
+            assert!(blue_fraction > previous);
+            previous = blue_fraction;
+        }
+    }
+
+    #[test]
+    fn srgb_round_trip() {
+        for i in 0..=20 {
+            let value = i as f32 / 20.0;
+            assert!((srgb_to_linear(linear_to_srgb(value)) - value).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn table_matches_exact_integration() {
+        for kelvin in [1200.0, 2700.0, 4321.0, SUN_TEMPERATURE, 9500.0, 33000.0] {
+            let difference = (blackbody_color(kelvin) - blackbody_color_exact(kelvin)).length();
+            assert!(difference < 1e-2, "{} K difiere en {}", kelvin, difference);
+        }
+    }
+}
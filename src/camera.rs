@@ -0,0 +1,122 @@
+use crate::math::{Mat4, Vec3};
+use crate::uniforms::{
+    create_infinite_perspective_matrix, create_orthographic_matrix, create_perspective_matrix,
+    create_reverse_z_orthographic_matrix, create_reverse_z_perspective_matrix, create_view_matrix,
+};
+
+/// Tipo de proyección de la cámara
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Projection {
+    /// Perspectiva con campo de visión vertical `fov` (radianes)
+    Perspective { fov: f32 },
+    /// Ortográfica: `size` es la altura visible en unidades del mundo
+    Orthographic { size: f32 },
+}
+
+/// Cámara de la escena: genera las matrices de vista y proyección
+pub struct Camera {
+    pub position: Vec3,         // Posición de la cámara
+    pub target: Vec3,           // Hacia dónde mira
+    pub up: Vec3,               // Vector "arriba"
+    pub projection: Projection,
+    pub near: f32,              // Plano cercano
+    pub far: f32,               // Plano lejano (ignorado si `infinite_far`)
+    pub aspect: f32,            // Ancho / alto del viewport
+    pub reverse_z: bool,        // Profundidad invertida: cercano = 1, lejano = 0
+    pub infinite_far: bool,     // Perspectiva sin plano lejano
+}
+
+impl Camera {
+    /// Cámara en perspectiva con FOV de 45° y planos 0.1 - 100
+    pub fn new(position: Vec3, target: Vec3, up: Vec3, aspect: f32) -> Self {
+        Self {
+            position,
+            target,
+            up,
+            projection: Projection::Perspective { fov: 45.0_f32.to_radians() },
+            near: 0.1,
+            far: 100.0,
+            aspect,
+            reverse_z: false,
+            infinite_far: false,
+        }
+    }
+
+    /// Actualiza la relación de aspecto (se llama cuando cambia el tamaño de la ventana)
+    pub fn set_viewport_size(&mut self, width: f32, height: f32) {
+        if height > 0.0 {
+            self.aspect = width / height;
+        }
+    }
+
+    /// Alterna entre perspectiva y ortográfica conservando el tamaño aparente del objetivo
+    pub fn toggle_projection(&mut self) {
+        let distance = (self.target - self.position).length();
+        self.projection = match self.projection {
+            Projection::Perspective { fov } => Projection::Orthographic {
+                size: 2.0 * distance * (fov / 2.0).tan(),
+            },
+            Projection::Orthographic { size } => Projection::Perspective {
+                fov: 2.0 * (size / (2.0 * distance.max(self.near))).atan(),
+            },
+        };
+    }
+
+    pub fn view_matrix(&self) -> Mat4 {
+        create_view_matrix(self.position, self.target, self.up)
+    }
+
+    pub fn projection_matrix(&self) -> Mat4 {
+        match self.projection {
+            Projection::Perspective { fov } => {
+                if self.infinite_far {
+                    create_infinite_perspective_matrix(fov, self.aspect, self.near, self.reverse_z)
+                } else if self.reverse_z {
+                    create_reverse_z_perspective_matrix(fov, self.aspect, self.near, self.far)
+                } else {
+                    create_perspective_matrix(fov, self.aspect, self.near, self.far)
+                }
+            }
+            Projection::Orthographic { size } => {
+                let half_height = size / 2.0;
+                let half_width = half_height * self.aspect;
+                if self.reverse_z {
+                    create_reverse_z_orthographic_matrix(-half_width, half_width, -half_height, half_height, self.near, self.far)
+                } else {
+                    create_orthographic_matrix(-half_width, half_width, -half_height, half_height, self.near, self.far)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn camera() -> Camera {
+        Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::ZERO, Vec3::Y, 1.5)
+    }
+
+    #[test]
+    fn view_matrix_puts_the_target_in_front() {
+        let view = camera().view_matrix();
+        assert!(view.transform_point(Vec3::new(0.0, 0.0, 5.0)).length() < 1e-5);
+        assert!((view.transform_point(Vec3::ZERO) - Vec3::new(0.0, 0.0, -5.0)).length() < 1e-5);
+    }
+
+    #[test]
+    fn infinite_far_ignores_the_far_plane() {
+        let mut camera = camera();
+        camera.infinite_far = true;
+        for reverse_z in [false, true] {
+            camera.reverse_z = reverse_z;
+            let projection = camera.projection_matrix();
+            let depth = |distance: f32| projection.transform_point(Vec3::new(0.0, 0.0, -distance)).z;
+            let near = if reverse_z { 1.0 } else { -1.0 };
+            assert!((depth(camera.near) - near).abs() < 1e-4);
+            // Mucho más allá de `far` el punto sigue dentro del volumen de recorte
+            assert!(depth(camera.far * 1e4).abs() < 1.0, "{}", depth(camera.far * 1e4));
+        }
+    }
+}
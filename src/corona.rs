@@ -0,0 +1,167 @@
+//! CORONA
+//! Halo alrededor de la estrella calculado en espacio de pantalla: un brillo que decae
+//! radialmente desde el limbo, modulado por "streamers" (rayos de ruido) que se alejan
+//! de la estrella con el tiempo. Se suma al framebuffer con mezcla aditiva solo donde
+//! no se dibujó la esfera
+
+use raylib::prelude::*;
+use std::f32::consts::TAU;
+use crate::framebuffer::Framebuffer;
+use crate::uniforms::Uniforms;
+use crate::math::{smoothstep, Vec3};
+use crate::noise::default_generator;
+use crate::blackbody::{blackbody_color, linear_to_srgb};
+
+/// Parámetros de la corona
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Corona {
+    pub intensity: f32,         // Brillo justo sobre el limbo
+    pub extent: f32,            // Radio máximo del halo (en radios estelares)
+    pub streamer_count: f32,    // Cantidad aproximada de streamers alrededor del disco
+    pub streamer_contrast: f32, // 0 = halo liso, 1 = rayos muy marcados
+    pub speed: f32,             // Velocidad a la que los streamers se alejan
+}
+
+impl Default for Corona {
+    fn default() -> Self {
+        Self {
+            intensity: 0.5,
+            extent: 3.5,
+            streamer_count: 10.0,
+            streamer_contrast: 0.7,
+            speed: 0.15,
+        }
+    }
+}
+
+/// Centro y radio (en píxeles) del disco de una esfera vista por la cámara
+pub fn screen_circle(uniforms: &Uniforms, center: Vec3, radius: f32) -> Option<(Vector2, f32)> {
+    let screen_center = uniforms.world_to_screen(center)?;
+
+    // En perspectiva la silueta de una esfera es un poco más grande que su radio:
+    // los rayos tangentes la tocan antes del ecuador visible
+    let silhouette_radius = if uniforms.is_orthographic() {
+        radius
+    } else {
+        let distance = (uniforms.camera_position - center).length();
+        if distance <= radius {
+            return None;
+        }
+        radius * distance / (distance * distance - radius * radius).sqrt()
+    };
+
+    let camera_right = uniforms.view_matrix.row(0).truncate().normalize();
+    let screen_edge = uniforms.world_to_screen(center + camera_right * silhouette_radius)?;
+    let screen_radius = Vector2::new(screen_edge.x - screen_center.x, screen_edge.y - screen_center.y).length();
+
+    Some((Vector2::new(screen_center.x, screen_center.y), screen_radius))
+}
+
+impl Corona {
+    /// Brillo radial a `r` radios estelares del centro (sin streamers)
+    /// La corona K cae muy rápido cerca del limbo y más despacio lejos de él
+    pub fn radial_profile(&self, r: f32) -> f32 {
+        let r = r.max(1.0);
+        let falloff = 0.9 * r.powi(-7) + 0.1 * r.powi(-3);
+        self.intensity * falloff * (1.0 - smoothstep(self.extent * 0.4, self.extent, r))
+    }
+
+    /// Modulación angular de los streamers (1 en promedio)
+    /// `drift` es cuánto se alejaron los rayos; si `period` no es 0 el ruido se repite
+    /// cada `period` unidades de `drift` (para animaciones en bucle)
+    fn streamers(&self, angle: f32, r: f32, drift: f32, period: u32) -> f32 {
+        // El ruido se muestrea sobre un círculo para que sea periódico en el ángulo,
+        // y varía despacio con el radio para que los rasgos se vean como rayos
+        let ring = self.streamer_count / TAU * 2.0;
+        let noise = default_generator().turbulence_periodic(
+            angle.cos() * ring,
+            angle.sin() * ring,
+            r * 0.6 - drift,
+            3,
+            [0, 0, period],
+        ) / 1.75;
+        let rays = (2.0 * noise).powi(3);
+        1.0 + (rays - 1.0) * self.streamer_contrast
+    }
+
+    /// Dibuja la corona alrededor de una esfera de radio `radius` centrada en `center`
+    pub fn render(&self, framebuffer: &mut Framebuffer, uniforms: &Uniforms, center: Vec3, radius: f32) {
+        let Some((screen_center, screen_radius)) = screen_circle(uniforms, center, radius) else {
+            return;
+        };
+        if screen_radius < 1.0 {
+            return;
+        }
+
+        let color = blackbody_color(uniforms.star_temperature);
+        // En bucle los rayos avanzan un número entero de periodos del ruido por vuelta
+        let (drift, period) = match &uniforms.time_loop {
+            Some(time_loop) => {
+                let cycles = time_loop.cycles(self.speed);
+                (uniforms.time * cycles as f32 / time_loop.duration, cycles)
+            }
+            None => (uniforms.time * self.speed, 0),
+        };
+        let reach = screen_radius * self.extent;
+        let min_x = ((screen_center.x - reach).floor() as i32).max(0);
+        let max_x = ((screen_center.x + reach).ceil() as i32).min(framebuffer.width as i32 - 1);
+        let min_y = ((screen_center.y - reach).floor() as i32).max(0);
+        let max_y = ((screen_center.y + reach).ceil() as i32).min(framebuffer.height as i32 - 1);
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                // La corona queda detrás de la estrella: no se dibuja sobre el disco
+                if !framebuffer.is_background(x, y) {
+                    continue;
+                }
+
+                let dx = x as f32 + 0.5 - screen_center.x;
+                let dy = y as f32 + 0.5 - screen_center.y;
+                let r = (dx * dx + dy * dy).sqrt() / screen_radius;
+                if r > self.extent {
+                    continue;
+                }
+
+                // Por debajo de ~1e-4 lineal ni siquiera alcanza medio nivel de 8 bits en sRGB
+                let brightness = self.radial_profile(r);
+                if brightness < 1e-4 {
+                    continue;
+                }
+                let brightness = brightness * self.streamers(dy.atan2(dx), r, drift, period);
+
+                framebuffer.blend_add(x, y, Color::new(
+                    (linear_to_srgb(color.x * brightness) * 255.0) as u8,
+                    (linear_to_srgb(color.y * brightness) * 255.0) as u8,
+                    (linear_to_srgb(color.z * brightness) * 255.0) as u8,
+                    255,
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn radial_profile_fades_out() {
+        let corona = Corona::default();
+        assert!((corona.radial_profile(1.0) - corona.intensity).abs() < 1e-6);
+        let mut previous = corona.radial_profile(1.0);
+        for step in 1..25 {
+            let current = corona.radial_profile(1.0 + step as f32 * 0.1);
+            assert!(current < previous);
+            previous = current;
+        }
+        assert_eq!(corona.radial_profile(corona.extent), 0.0);
+    }
+
+    #[test]
+    fn smooth_halo_without_streamer_contrast() {
+        let corona = Corona { streamer_contrast: 0.0, ..Corona::default() };
+        for angle in [0.0, 1.0, 2.5, 4.0] {
+            assert_eq!(corona.streamers(angle, 1.5, 3.0 * corona.speed, 0), 1.0);
+        }
+    }
+}
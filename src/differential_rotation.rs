@@ -0,0 +1,154 @@
+//! ROTACIÓN DIFERENCIAL
+//! Una estrella no gira como un sólido: el ecuador da la vuelta más rápido que los polos
+//! (el Sol tarda ~25 días en el ecuador y ~35 cerca de los polos). La velocidad angular
+//! sigue el perfil ω(φ) = A + B sin²φ + C sin⁴φ, con φ la latitud
+
+use crate::math::Vec3;
+
+/// Perfil de velocidad angular por latitud (radianes por segundo)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DifferentialRotation {
+    pub a: f32, // Velocidad angular del ecuador
+    pub b: f32, // Término en sin²φ
+    pub c: f32, // Término en sin⁴φ
+}
+
+impl DifferentialRotation {
+    /// Coeficientes de Snodgrass para el Sol (grados por día)
+    const SNODGRASS: (f32, f32, f32) = (14.713, -2.396, -1.787);
+
+    pub fn new(a: f32, b: f32, c: f32) -> Self {
+        Self { a, b, c }
+    }
+
+    /// Perfil del Sol escalado para que el ecuador gire a `equatorial_velocity`
+    pub fn solar(equatorial_velocity: f32) -> Self {
+        let (a, b, c) = Self::SNODGRASS;
+        let scale = equatorial_velocity / a;
+        Self::new(equatorial_velocity, b * scale, c * scale)
+    }
+
+    /// Velocidad angular a una latitud dada (radianes)
+    pub fn angular_velocity(&self, latitude: f32) -> f32 {
+        let sin2 = latitude.sin().powi(2);
+        self.a + self.b * sin2 + self.c * sin2 * sin2
+    }
+
+    /// Ángulo girado por la superficie a una latitud tras `time` segundos
+    pub fn angle(&self, latitude: f32, time: f32) -> f32 {
+        self.angular_velocity(latitude) * time
+    }
+
+    /// Lleva un punto de la esfera unitaria (espacio objeto, eje Y) al marco que gira
+    /// con la superficie a su latitud: muestrear ahí el ruido hace que los rasgos se
+    /// desplacen y se cizallen con el tiempo
+    pub fn surface_frame(&self, position: Vec3, time: f32) -> Vec3 {
+        self.surface_frame_since(position, time, 0.0)
+    }
+
+    /// Como `surface_frame`, pero la cizalla respecto al ecuador solo se acumula desde
+    /// `since`: para patrones que se renuevan (como los gránulos), que no viven lo
+    /// suficiente para estirarse indefinidamente
+    pub fn surface_frame_since(&self, position: Vec3, time: f32, since: f32) -> Vec3 {
+        rotate_y(position, -self.frame_angle(position, time, since))
+    }
+
+    /// Devuelve a espacio objeto una dirección expresada en el marco de `position`
+    /// (por ejemplo, el gradiente de un ruido muestreado en `surface_frame`)
+    /// No incluye la variación de la cizalla con la latitud, que es lenta frente al ruido
+    pub fn surface_frame_to_object(&self, position: Vec3, direction: Vec3, time: f32) -> Vec3 {
+        rotate_y(direction, self.frame_angle(position, time, 0.0))
+    }
+
+    /// Lleva a espacio objeto el gradiente de un campo muestreado en `surface_frame_since`
+    /// Es la regla de la cadena completa: además de deshacer el giro suma la variación
+    /// de la cizalla con la latitud, que solo mueve el ángulo a lo largo de Y
+    pub fn surface_frame_since_gradient(&self, position: Vec3, gradient: Vec3, time: f32, since: f32) -> Vec3 {
+        let angle = self.frame_angle(position, time, since);
+        // ∂marco/∂ángulo: el punto del marco girado un cuarto de vuelta alrededor de Y
+        let frame = rotate_y(position, -angle);
+        let d_frame = Vec3::new(-frame.z, 0.0, frame.x);
+        // sin φ = y, así que la cizalla B y² + C y⁴ se deriva directamente en y
+        let y = position.y.clamp(-1.0, 1.0);
+        let d_angle = (2.0 * self.b * y + 4.0 * self.c * y * y * y) * (time - since);
+        rotate_y(gradient, angle) + Vec3::new(0.0, gradient.dot(d_frame) * d_angle, 0.0)
+    }
+
+    /// Ángulo girado por el marco de `position`
+    fn frame_angle(&self, position: Vec3, time: f32, since: f32) -> f32 {
+        let latitude = position.y.clamp(-1.0, 1.0).asin();
+        let shear = self.angular_velocity(latitude) - self.a;
+        self.a * time + shear * (time - since)
+    }
+}
+
+impl Default for DifferentialRotation {
+    fn default() -> Self {
+        Self::solar(0.3)
+    }
+}
+
+/// Gira un vector alrededor del eje Y
+fn rotate_y(v: Vec3, angle: f32) -> Vec3 {
+    let (sin, cos) = angle.sin_cos();
+    Vec3::new(v.x * cos + v.z * sin, v.y, -v.x * sin + v.z * cos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equator_rotates_faster_than_poles() {
+        let sun = DifferentialRotation::solar(0.3);
+        assert!((sun.angular_velocity(0.0) - 0.3).abs() < 1e-6);
+        assert!(sun.angular_velocity(0.5) < sun.angular_velocity(0.2));
+        // Cerca de los polos el Sol gira ~30% más lento
+        let ratio = sun.angular_velocity(80.0_f32.to_radians()) / sun.angular_velocity(0.0);
+        assert!((0.65..0.75).contains(&ratio));
+    }
+
+    #[test]
+    fn surface_frame_keeps_latitude_and_length() {
+        let sun = DifferentialRotation::solar(0.3);
+        let p = Vec3::new(0.3, 0.5, 0.8).normalize();
+        let q = sun.surface_frame(p, 17.0);
+        assert!((q.length() - 1.0).abs() < 1e-5);
+        assert!((q.y - p.y).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rigid_rotation_does_not_shear() {
+        // Con B = C = 0 todas las latitudes giran igual
+        let rigid = DifferentialRotation::new(0.3, 0.0, 0.0);
+        let equator = rigid.surface_frame(Vec3::Z, 5.0);
+        let high = rigid.surface_frame(Vec3::new(0.0, 0.8, 0.6), 5.0);
+        // Mismo ángulo de longitud en ambas latitudes
+        assert!((equator.x.atan2(equator.z) - high.x.atan2(high.z)).abs() < 1e-5);
+
+        let sun = DifferentialRotation::solar(0.3);
+        let equator = sun.surface_frame(Vec3::Z, 5.0);
+        let high = sun.surface_frame(Vec3::new(0.0, 0.8, 0.6), 5.0);
+        assert!((equator.x.atan2(equator.z) - high.x.atan2(high.z)).abs() > 0.05);
+
+        // Un patrón recién nacido todavía no está cizallado
+        let equator = sun.surface_frame_since(Vec3::Z, 5.0, 5.0);
+        let high = sun.surface_frame_since(Vec3::new(0.0, 0.8, 0.6), 5.0, 5.0);
+        assert!((equator.x.atan2(equator.z) - high.x.atan2(high.z)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn frame_gradient_includes_the_shear() {
+        // Campo lineal en el marco: su gradiente en espacio objeto por diferencias finitas
+        let sun = DifferentialRotation::solar(0.3);
+        let c = Vec3::new(0.4, -0.7, 0.9);
+        let field = |p: Vec3| sun.surface_frame_since(p, 6.0, 1.0).dot(c);
+        let p = Vec3::new(0.3, 0.6, 0.7);
+        let gradient = sun.surface_frame_since_gradient(p, c, 6.0, 1.0);
+        let h = 1e-3;
+        for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+            let numeric = (field(p + axis * h) - field(p - axis * h)) / (2.0 * h);
+            assert!((gradient.dot(axis) - numeric).abs() < 1e-3, "{} vs {numeric}", gradient.dot(axis));
+        }
+    }
+}
@@ -4,6 +4,8 @@ use raylib::prelude::*;
 /// Este es el buffer donde se renderizan todos los píxeles de la estrella
 pub struct Framebuffer {
     pub pixels: Vec<Color>,
+    pub depth_buffer: Vec<f32>,   // Profundidad del fragmento más cercano en cada píxel
+    pub reverse_z: bool,          // Con Z invertida los valores mayores están más cerca
     pub width: u32,
     pub height: u32,
     pub current_color: Color,
@@ -17,6 +19,8 @@ impl Framebuffer {
         let total_pixels = (width * height) as usize;
         Self {
             pixels: vec![Color::BLACK; total_pixels],
+            depth_buffer: vec![f32::INFINITY; total_pixels],
+            reverse_z: false,
             width,
             height,
             current_color: Color::WHITE,
@@ -30,6 +34,70 @@ impl Framebuffer {
         for pixel in &mut self.pixels {
             *pixel = self.background_color;
         }
+        
+        // La profundidad inicial es "infinitamente lejos" según la convención de Z
+        let far_depth = if self.reverse_z { f32::NEG_INFINITY } else { f32::INFINITY };
+        for depth in &mut self.depth_buffer {
+            *depth = far_depth;
+        }
+    }
+
+    /// Cambiar el tamaño del framebuffer (por ejemplo, al redimensionar la ventana)
+    pub fn resize(&mut self, width: u32, height: u32) {
+        let total_pixels = (width * height) as usize;
+        self.width = width;
+        self.height = height;
+        self.pixels = vec![self.background_color; total_pixels];
+        self.depth_buffer = vec![f32::INFINITY; total_pixels];
+        self.texture = None;
+        self.clear();
+    }
+
+    pub fn set_reverse_z(&mut self, reverse_z: bool) {
+        self.reverse_z = reverse_z;
+    }
+
+    /// Prueba de profundidad: si el fragmento está más cerca que lo dibujado
+    /// guarda su profundidad y retorna true
+    pub fn depth_test(&mut self, x: i32, y: i32, depth: f32) -> bool {
+        if x < 0 || x >= self.width as i32 || y < 0 || y >= self.height as i32 {
+            return false;
+        }
+        
+        let index = (y as u32 * self.width + x as u32) as usize;
+        let closer = if self.reverse_z {
+            depth > self.depth_buffer[index]
+        } else {
+            depth < self.depth_buffer[index]
+        };
+        
+        if closer {
+            self.depth_buffer[index] = depth;
+        }
+        closer
+    }
+
+    /// Prueba de profundidad sin escribir en el buffer: para efectos transparentes
+    /// (partículas, brillos) que deben ocultarse detrás de la geometría sin tapar nada
+    pub fn depth_passes(&self, x: i32, y: i32, depth: f32) -> bool {
+        if x < 0 || x >= self.width as i32 || y < 0 || y >= self.height as i32 {
+            return false;
+        }
+        let stored = self.depth_buffer[(y as u32 * self.width + x as u32) as usize];
+        if self.reverse_z { depth > stored } else { depth < stored }
+    }
+
+    /// Profundidad guardada en un píxel (lejana si está fuera de la pantalla)
+    pub fn depth_at(&self, x: i32, y: i32) -> f32 {
+        if x < 0 || x >= self.width as i32 || y < 0 || y >= self.height as i32 {
+            return if self.reverse_z { f32::NEG_INFINITY } else { f32::INFINITY };
+        }
+        self.depth_buffer[(y as u32 * self.width + x as u32) as usize]
+    }
+
+    /// true si ningún fragmento ha escrito en el píxel desde el último `clear`
+    pub fn is_background(&self, x: i32, y: i32) -> bool {
+        self.depth_at(x, y).is_infinite()
     }
 
     pub fn set_background_color(&mut self, color: Color) {
@@ -100,6 +168,18 @@ impl Framebuffer {
         }
     }
 
+    /// Mezcla aditiva: suma el color al píxel (saturando en 255)
+    /// Se usa para brillos y emisión, que solo agregan luz
+    pub fn blend_add(&mut self, x: i32, y: i32, color: Color) {
+        if x >= 0 && x < self.width as i32 && y >= 0 && y < self.height as i32 {
+            let index = (y as u32 * self.width + x as u32) as usize;
+            let pixel = &mut self.pixels[index];
+            pixel.r = pixel.r.saturating_add(color.r);
+            pixel.g = pixel.g.saturating_add(color.g);
+            pixel.b = pixel.b.saturating_add(color.b);
+        }
+    }
+
     /// Método auxiliar para dibujar un punto (usado en rasterización)
     pub fn point(&mut self, x: i32, y: i32, color: Color) {
         if x >= 0 && x < self.width as i32 && y >= 0 && y < self.height as i32 {
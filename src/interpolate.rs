@@ -0,0 +1,140 @@
+use raylib::prelude::*;
+use crate::math::{Vec3, Vec4};
+
+/// INTERPOLACIÓN DE VARYINGS
+/// Un tipo que implementa `Interpolate` puede viajar del vertex shader al fragment
+/// shader: el rasterizador lo combina con los pesos baricéntricos de cada píxel
+/// (ya corregidos por perspectiva), sin saber qué contiene
+pub trait Interpolate: Sized {
+    /// Combinación baricéntrica `a * wa + b * wb + c * wc` con `weights = (wa, wb, wc)`
+    fn interpolate(a: &Self, b: &Self, c: &Self, weights: (f32, f32, f32)) -> Self;
+}
+
+/// Implementa `Interpolate` para tipos que se pueden sumar y escalar
+macro_rules! impl_interpolate_linear {
+    ($($t:ty),+) => {
+        $(
+            impl Interpolate for $t {
+                fn interpolate(a: &Self, b: &Self, c: &Self, (wa, wb, wc): (f32, f32, f32)) -> Self {
+                    *a * wa + *b * wb + *c * wc
+                }
+            }
+        )+
+    };
+}
+
+impl_interpolate_linear!(f32, Vector2, Vector3, Vec3, Vec4);
+
+impl Interpolate for () {
+    fn interpolate(_a: &(), _b: &(), _c: &(), _weights: (f32, f32, f32)) {}
+}
+
+/// Las tuplas se interpolan componente a componente
+macro_rules! impl_interpolate_tuple {
+    ($($name:ident : $index:tt),+) => {
+        impl<$($name: Interpolate),+> Interpolate for ($($name,)+) {
+            fn interpolate(a: &Self, b: &Self, c: &Self, weights: (f32, f32, f32)) -> Self {
+                ($($name::interpolate(&a.$index, &b.$index, &c.$index, weights),)+)
+            }
+        }
+    };
+}
+
+impl_interpolate_tuple!(A: 0);
+impl_interpolate_tuple!(A: 0, B: 1);
+impl_interpolate_tuple!(A: 0, B: 1, C: 2);
+impl_interpolate_tuple!(A: 0, B: 1, C: 2, D: 3);
+
+impl<T: Interpolate, const N: usize> Interpolate for [T; N] {
+    fn interpolate(a: &Self, b: &Self, c: &Self, weights: (f32, f32, f32)) -> Self {
+        std::array::from_fn(|i| T::interpolate(&a[i], &b[i], &c[i], weights))
+    }
+}
+
+/// Implementa `Interpolate` para un struct de varyings interpolando cada campo
+///
+/// ```ignore
+/// #[derive(Clone)]
+/// struct MisVaryings { uv: Vector2, tangente: Vec3 }
+/// impl_interpolate!(MisVaryings { uv, tangente });
+/// ```
+#[macro_export]
+macro_rules! impl_interpolate {
+    ($t:ident { $($field:ident),+ $(,)? }) => {
+        impl $crate::interpolate::Interpolate for $t {
+            fn interpolate(a: &Self, b: &Self, c: &Self, weights: (f32, f32, f32)) -> Self {
+                Self {
+                    $($field: $crate::interpolate::Interpolate::interpolate(&a.$field, &b.$field, &c.$field, weights)),+
+                }
+            }
+        }
+    };
+}
+
+/// Pesos baricéntricos corregidos por perspectiva
+/// Los pesos de pantalla (w, v, u) interpolan linealmente en pantalla, pero los atributos
+/// varían linealmente en el espacio 3D: se interpola atributo / w_clip y luego se divide
+/// entre el 1 / w_clip interpolado
+pub fn perspective_correct_weights(weights: (f32, f32, f32), clip_w: (f32, f32, f32)) -> (f32, f32, f32) {
+    let a = weights.0 / clip_w.0;
+    let b = weights.1 / clip_w.1;
+    let c = weights.2 / clip_w.2;
+    let sum = a + b + c;
+
+    if sum.abs() < 1e-12 || !sum.is_finite() {
+        return weights;
+    }
+
+    (a / sum, b / sum, c / sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct TestVaryings {
+        uv: Vector2,
+        intensity: f32,
+    }
+
+    crate::impl_interpolate!(TestVaryings { uv, intensity });
+
+    #[test]
+    fn interpolates_scalars_vectors_and_tuples() {
+        let weights = (0.5, 0.25, 0.25);
+        assert_eq!(f32::interpolate(&4.0, &8.0, &0.0, weights), 4.0);
+
+        let v = Vec3::interpolate(&Vec3::X, &Vec3::Y, &Vec3::Z, weights);
+        assert_eq!(v, Vec3::new(0.5, 0.25, 0.25));
+
+        let (s, t) = <(f32, f32)>::interpolate(&(1.0, 0.0), &(0.0, 1.0), &(0.0, 0.0), weights);
+        assert_eq!((s, t), (0.5, 0.25));
+
+        let array = <[f32; 2]>::interpolate(&[1.0, 2.0], &[1.0, 2.0], &[1.0, 2.0], weights);
+        assert_eq!(array, [1.0, 2.0]);
+    }
+
+    #[test]
+    fn macro_interpolates_every_field() {
+        let a = TestVaryings { uv: Vector2::new(0.0, 0.0), intensity: 1.0 };
+        let b = TestVaryings { uv: Vector2::new(1.0, 0.0), intensity: 0.0 };
+        let c = TestVaryings { uv: Vector2::new(0.0, 1.0), intensity: 0.0 };
+        let result = TestVaryings::interpolate(&a, &b, &c, (0.0, 0.5, 0.5));
+        assert_eq!(result, TestVaryings { uv: Vector2::new(0.5, 0.5), intensity: 0.0 });
+    }
+
+    #[test]
+    fn perspective_weights_favor_closer_vertices() {
+        // Con la misma w no hay corrección
+        let same = perspective_correct_weights((0.2, 0.3, 0.5), (2.0, 2.0, 2.0));
+        assert!((same.0 - 0.2).abs() < 1e-6 && (same.1 - 0.3).abs() < 1e-6);
+
+        // El punto medio en pantalla de un vértice cercano (w = 1) y uno lejano (w = 3)
+        // corresponde a 3/4 del camino hacia el cercano en espacio 3D
+        let corrected = perspective_correct_weights((0.5, 0.5, 0.0), (1.0, 3.0, 1.0));
+        assert!((corrected.0 - 0.75).abs() < 1e-6);
+        assert!((corrected.1 - 0.25).abs() < 1e-6);
+        assert!(corrected.2.abs() < 1e-6);
+    }
+}
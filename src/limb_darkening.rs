@@ -0,0 +1,100 @@
+/// Modelos de oscurecimiento hacia el limbo
+/// El borde de una estrella se ve más oscuro (y más rojo) que el centro porque ahí
+/// la línea de visión atraviesa capas más altas y frías de la fotosfera
+/// Todos se expresan en función de μ = cos(ángulo entre la normal y la dirección de vista):
+/// μ = 1 en el centro del disco y μ = 0 en el limbo
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LimbDarkening {
+    /// Sin oscurecimiento (disco uniforme)
+    None,
+    /// I(μ) = 1 - u(1 - μ)
+    Linear { u: f32 },
+    /// I(μ) = 1 - a(1 - μ) - b(1 - μ)²
+    Quadratic { a: f32, b: f32 },
+    /// Ley no lineal de Claret: I(μ) = 1 - Σ aₖ(1 - μ^(k/2)), k = 1..4
+    Claret { a1: f32, a2: f32, a3: f32, a4: f32 },
+}
+
+impl LimbDarkening {
+    /// Coeficientes aproximados del Sol en el visible
+    pub const SUN_LINEAR: LimbDarkening = LimbDarkening::Linear { u: 0.6 };
+    pub const SUN_QUADRATIC: LimbDarkening = LimbDarkening::Quadratic { a: 0.44, b: 0.26 };
+    pub const SUN_CLARET: LimbDarkening = LimbDarkening::Claret { a1: 0.5169, a2: -0.0211, a3: 0.6944, a4: -0.3892 };
+
+    /// Intensidad relativa al centro del disco para un μ dado
+    pub fn intensity(&self, mu: f32) -> f32 {
+        let mu = mu.clamp(0.0, 1.0);
+        let one_minus_mu = 1.0 - mu;
+
+        let intensity = match *self {
+            LimbDarkening::None => 1.0,
+            LimbDarkening::Linear { u } => 1.0 - u * one_minus_mu,
+            LimbDarkening::Quadratic { a, b } => 1.0 - a * one_minus_mu - b * one_minus_mu * one_minus_mu,
+            LimbDarkening::Claret { a1, a2, a3, a4 } => {
+                let sqrt_mu = mu.sqrt();
+                1.0 - a1 * (1.0 - sqrt_mu)
+                    - a2 * one_minus_mu
+                    - a3 * (1.0 - mu * sqrt_mu)
+                    - a4 * (1.0 - mu * mu)
+            }
+        };
+
+        intensity.max(0.0)
+    }
+
+    /// Siguiente modelo (para recorrerlos desde el teclado)
+    pub fn next(&self) -> LimbDarkening {
+        match self {
+            LimbDarkening::None => LimbDarkening::SUN_LINEAR,
+            LimbDarkening::Linear { .. } => LimbDarkening::SUN_QUADRATIC,
+            LimbDarkening::Quadratic { .. } => LimbDarkening::SUN_CLARET,
+            LimbDarkening::Claret { .. } => LimbDarkening::None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LimbDarkening::None => "ninguno",
+            LimbDarkening::Linear { .. } => "lineal",
+            LimbDarkening::Quadratic { .. } => "cuadrático",
+            LimbDarkening::Claret { .. } => "Claret (no lineal)",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODELS: [LimbDarkening; 4] = [
+        LimbDarkening::None,
+        LimbDarkening::SUN_LINEAR,
+        LimbDarkening::SUN_QUADRATIC,
+        LimbDarkening::SUN_CLARET,
+    ];
+
+    #[test]
+    fn disk_center_has_full_intensity() {
+        for model in MODELS {
+            assert!((model.intensity(1.0) - 1.0).abs() < 1e-6, "{:?}", model);
+        }
+    }
+
+    #[test]
+    fn intensity_decreases_towards_the_limb() {
+        for model in &MODELS[1..] {
+            let mut previous = model.intensity(1.0);
+            for step in (0..10).rev() {
+                let current = model.intensity(step as f32 / 10.0);
+                assert!(current < previous, "{:?} no decrece en μ = {}", model, step);
+                previous = current;
+            }
+        }
+    }
+
+    #[test]
+    fn linear_law_matches_formula() {
+        assert!((LimbDarkening::Linear { u: 0.6 }.intensity(0.0) - 0.4).abs() < 1e-6);
+        assert!((LimbDarkening::Linear { u: 0.6 }.intensity(0.5) - 0.7).abs() < 1e-6);
+    }
+}
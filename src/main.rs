@@ -1,87 +1,374 @@
 mod framebuffer;
 mod vertex;
+mod math;
+mod transform;
+mod camera;
 mod uniforms;
 mod noise;
+mod interpolate;
+mod blackbody;
+mod limb_darkening;
+mod photosphere;
+mod random;
+mod sunspots;
+mod differential_rotation;
+mod corona;
+mod volumetric;
+mod particles;
 mod shaders;
+mod star_shader;
 mod triangle;
 mod obj_loader;
+mod subdivision;
 mod renderer;
+mod benchmark;
 
 use raylib::prelude::*;
 use framebuffer::Framebuffer;
 use uniforms::*;
 use obj_loader::ObjModel;
-use renderer::render;
+use subdivision::SubdivisionScheme;
+use math::{Quat, Vec3};
+use transform::Transform;
+use camera::Camera;
+use renderer::{render, render_spans};
+use star_shader::StarShader;
+use sunspots::{Sunspot, SunspotSystem};
+use differential_rotation::DifferentialRotation;
+use corona::Corona;
+use volumetric::VolumetricShell;
+use particles::{Emitter, ParticleSystem};
+use vertex::Vertex;
+use noise::TimeLoop;
 
 const WIDTH: u32 = 800;
 const HEIGHT: u32 = 600;
 
+// Niveles de subdivisión aplicados al modelo al cargarlo
+// (la distorsión a lo largo de la normal necesita una malla densa)
+const SUBDIVISION_LEVELS: u32 = 1;
+
+// Velocidad de rotación del ecuador (radianes por segundo, regla de la mano derecha en Y)
+// Negativa: la superficie gira en sentido horario visto desde el polo norte, con el
+// frente moviéndose hacia la izquierda como en la versión original
+// La rotación de la superficie se aplica en el fragment shader, por latitud
+const ROTATION_SPEED: f32 = -0.3;
+
+// Semilla del ruido de la superficie (la tecla N genera otra estrella a partir de la siguiente)
+const STAR_SEED: u64 = 1;
+
+// Semilla de la simulación de manchas solares (la misma semilla produce las mismas manchas)
+const SUNSPOT_SEED: u64 = 1859;
+
+// Semilla de las partículas (viento solar, fulguraciones y eyecciones)
+const PARTICLE_SEED: u64 = 2003;
+
+// Inclinación del eje de rotación respecto al plano (la del Sol es de ~7.25°)
+const AXIAL_TILT_DEGREES: f32 = 7.25;
+// Velocidad de precesión del eje inclinado (radianes por segundo)
+const PRECESSION_SPEED: f32 = 0.05;
+
+// Temperaturas que se recorren con la tecla T: enana roja, el Sol, estrella blanca y gigante azul
+const STAR_TEMPERATURES: [f32; 4] = [3200.0, 5772.0, 9500.0, 25000.0];
+
+/// Vóxeles por eje de los volúmenes de ruido horneados (`--baked` o tecla Q)
+const BAKE_RESOLUTION: u32 = 128;
+
+/// Instante del ciclo de actividad del que se toman las manchas en modo bucle
+/// (la mitad del ciclo de `SunspotSystem`, con más actividad)
+const LOOP_SUNSPOT_TIME: f32 = 60.0;
+
+/// ¿Se pasó `flag` en la línea de comandos?
+fn has_flag(flag: &str) -> bool {
+    std::env::args().any(|arg| arg == flag)
+}
+
+/// Duración pedida con `--loop <segundos>` (None si no se pidió o no es válida)
+fn loop_duration() -> Option<f32> {
+    let args: Vec<String> = std::env::args().collect();
+    let index = args.iter().position(|arg| arg == "--loop")?;
+    match args.get(index + 1).and_then(|value| value.parse::<f32>().ok()) {
+        Some(seconds) if seconds > 0.0 => Some(seconds),
+        _ => {
+            eprintln!("⚠️  --loop necesita una duración en segundos mayor que 0");
+            None
+        }
+    }
+}
+
+/// Escena inicial que comparten la ventana y el benchmark
+struct Scene {
+    framebuffer: Framebuffer,
+    camera: Camera,
+    uniforms: Uniforms,
+    star_transform: Transform,
+    sphere_vertices: Vec<Vertex>,
+    sphere_indices: Vec<usize>,
+}
+
+impl Scene {
+    fn new() -> Self {
+        // Crear framebuffer personalizado
+        let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
+        framebuffer.set_background_color(Color::new(10, 5, 20, 255));
+
+        // Cargar geometría de la esfera desde archivo OBJ
+        let mut obj_model = ObjModel::load("assets/sphere.obj")
+            .expect("Error cargando el modelo OBJ. Asegúrate de que assets/sphere.obj existe.");
+        
+        // Refinar la malla para que la turbulencia del vertex shader se vea suave
+        let scheme = SubdivisionScheme::for_faces(&obj_model.faces);
+        obj_model.subdivide(scheme, SUBDIVISION_LEVELS);
+
+        // Configurar uniforms (parámetros globales para los shaders)
+        let mut uniforms = Uniforms::new();
+        
+        // Configurar la cámara (perspectiva con Z invertida para mejor precisión de profundidad)
+        let mut camera = Camera::new(
+            Vec3::new(0.0, 0.0, 5.0),  // Posición de la cámara
+            Vec3::new(0.0, 0.0, 0.0),  // Hacia dónde mira
+            Vec3::new(0.0, 1.0, 0.0),  // Vector "arriba"
+            WIDTH as f32 / HEIGHT as f32,
+        );
+        camera.reverse_z = true;
+        framebuffer.set_reverse_z(camera.reverse_z);
+        
+        // Configurar matrices de transformación
+        uniforms.view_matrix = camera.view_matrix();
+        uniforms.projection_matrix = camera.projection_matrix();
+        uniforms.viewport_matrix = create_viewport_matrix(WIDTH as f32, HEIGHT as f32);
+        uniforms.camera_position = camera.position;
+        
+        // Parámetros ajustables de la estrella
+        uniforms.noise_scale = 2.0;
+        uniforms.turbulence_intensity = 0.8;
+
+        // La superficie gira más rápido en el ecuador que en los polos (perfil del Sol)
+        uniforms.differential_rotation = DifferentialRotation::solar(ROTATION_SPEED);
+
+        let star_transform = Transform::new(Vec3::ZERO, Quat::IDENTITY, Vec3::splat(1.5));
+        uniforms.set_model_matrix(star_transform.to_matrix());
+
+        Self {
+            framebuffer,
+            camera,
+            uniforms,
+            star_transform,
+            sphere_vertices: obj_model.vertices,
+            sphere_indices: obj_model.indices,
+        }
+    }
+}
+
 fn main() {
+    // --bench: comparar el ruido exacto con el horneado sin abrir ventana
+    if has_flag("--bench") {
+        benchmark::run(STAR_SEED, SUNSPOT_SEED, BAKE_RESOLUTION);
+        return;
+    }
+
     // Inicializar ventana con Raylib
     let (mut rl, thread) = raylib::init()
         .size(WIDTH as i32, HEIGHT as i32)
         .title("Estrella Animada - Gráficas por Computadora")
+        .resizable()
         .build();
 
     rl.set_target_fps(60);
 
-    // Crear framebuffer personalizado
-    let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
-    framebuffer.set_background_color(Color::new(10, 5, 20, 255));
-
-    // Cargar geometría de la esfera desde archivo OBJ
+    // Framebuffer, geometría, cámara y uniforms iniciales
     println!("📦 Cargando modelo 3D desde assets/sphere.obj...");
-    let obj_model = ObjModel::load("assets/sphere.obj")
-        .expect("Error cargando el modelo OBJ. Asegúrate de que assets/sphere.obj existe.");
+    let Scene { mut framebuffer, mut camera, mut uniforms, mut star_transform, sphere_vertices, sphere_indices } = Scene::new();
     
-    let sphere_vertices = obj_model.vertices;
-    let sphere_indices = obj_model.indices;
+    // Radio del modelo (para ubicar la corona alrededor de la silueta)
+    let mesh_radius = sphere_vertices
+        .iter()
+        .map(|vertex| Vec3::from(vertex.position).length())
+        .fold(0.0, f32::max);
 
-    // Configurar uniforms (parámetros globales para los shaders)
-    let mut uniforms = Uniforms::new();
-    
-    // Configurar matrices de transformación
-    uniforms.projection_matrix = create_perspective_matrix(
-        45.0_f32.to_radians(),
-        WIDTH as f32 / HEIGHT as f32,
-        0.1,
-        100.0
-    );
+    // Parámetros ajustables de la estrella
+    let mut temperature_index = 1;
+    uniforms.star_temperature = STAR_TEMPERATURES[temperature_index];
+
+    // Shader que define la apariencia de la estrella
+    let mut star_seed = STAR_SEED;
+    let mut star_shader = StarShader::new(star_seed);
     
-    uniforms.view_matrix = create_view_matrix(
-        Vector3::new(0.0, 0.0, 5.0),  // Posición de la cámara
-        Vector3::new(0.0, 0.0, 0.0),   // Hacia dónde mira
-        Vector3::new(0.0, 1.0, 0.0)    // Vector "arriba"
-    );
+    // Sombreado en tramos de 4 fragmentos con el ruido en lotes SIMD (B: uno por uno)
+    let mut batched_shading = true;
+
+    // Calidad del ruido: exacto en cada fragmento u horneado en volúmenes 3D (más rápido)
+    let mut baked_noise = has_flag("--baked");
+    if baked_noise {
+        println!("🧊 Horneando volúmenes de ruido ({}³ vóxeles)...", BAKE_RESOLUTION);
+        star_shader.bake(BAKE_RESOLUTION);
+    }
+
+    // Halo de la corona alrededor de la estrella
+    let corona = Corona::default();
+    let mut show_corona = true;
     
-    uniforms.viewport_matrix = create_viewport_matrix(WIDTH as f32, HEIGHT as f32);
+    // Protuberancias y lazos coronales (ray marching en una cáscara alrededor de la estrella)
+    let prominences = VolumetricShell::default();
+    let mut show_prominences = true;
+
+    // Manchas solares reproducibles a partir de la semilla
+    let sunspot_system = SunspotSystem::new(SUNSPOT_SEED);
     
-    // Parámetros ajustables de la estrella
-    uniforms.noise_scale = 2.0;
-    uniforms.turbulence_intensity = 0.8;
+    // Partículas: viento solar continuo desde varios puntos de la superficie
+    let mut particles = ParticleSystem::new(PARTICLE_SEED);
+    for i in 0..6 {
+        let longitude = i as f32 * std::f32::consts::TAU / 6.0;
+        let latitude = if i % 2 == 0 { 0.5 } else { -0.4 };
+        particles.add_emitter(Emitter::solar_wind(latitude, longitude));
+    }
+
+
+    // MODO BUCLE (--loop N): todo lo que depende del tiempo se ajusta para que la
+    // animación se repita exactamente cada N segundos (para exportar GIFs sin salto)
+    let time_loop = loop_duration().map(TimeLoop::new);
+    let mut precession_speed = PRECESSION_SPEED;
+    let mut loop_sunspots: Vec<Sunspot> = Vec::new();
+    if let Some(time_loop) = &time_loop {
+        println!("🔁 Animación en bucle de {} s", time_loop.duration);
+        uniforms.time_loop = Some(*time_loop);
+
+        // Rotación rígida (la cizalla nunca vuelve al inicio) con vueltas enteras por bucle
+        let spin = time_loop.angular_frequency(ROTATION_SPEED);
+        uniforms.differential_rotation = DifferentialRotation::new(spin, 0.0, 0.0);
+        precession_speed = time_loop.angular_frequency(PRECESSION_SPEED);
+
+        // Renovaciones enteras de cada capa de la fotosfera
+        uniforms.granulation = uniforms.granulation.looped(time_loop);
+        uniforms.supergranulation = uniforms.supergranulation.looped(time_loop);
+        uniforms.faculae = uniforms.faculae.looped(time_loop);
+
+        // Corriente de plasma fija y un número entero de fases por bucle
+        uniforms.plasma_flow.evolution = 0.0;
+        let phases = time_loop.cycles(1.0 / uniforms.plasma_flow_period);
+        uniforms.plasma_flow_period = time_loop.duration / phases as f32;
+
+        // Manchas congeladas en un instante del ciclo: solo giran con la superficie
+        let still = DifferentialRotation::new(0.0, 0.0, 0.0);
+        loop_sunspots = sunspot_system.active_spots(LOOP_SUNSPOT_TIME, &still);
+    }
 
     // Variables para animación
+    let mut elapsed: f32 = 0.0;
     let mut time: f32 = 0.0;
-    let mut rotation_angle: f32 = 0.0;
+    let axial_tilt = Quat::from_axis_angle(Vec3::Z, AXIAL_TILT_DEGREES.to_radians());
 
     // Loop principal
     while !rl.window_should_close() {
+        // Ajustar framebuffer, cámara y viewport si cambió el tamaño de la ventana
+        if rl.is_window_resized() {
+            let width = rl.get_screen_width().max(1) as u32;
+            let height = rl.get_screen_height().max(1) as u32;
+            framebuffer.resize(width, height);
+            camera.set_viewport_size(width as f32, height as f32);
+            uniforms.viewport_matrix = create_viewport_matrix(width as f32, height as f32);
+        }
+        
+        // P: alternar entre proyección perspectiva y ortográfica
+        if rl.is_key_pressed(KeyboardKey::KEY_P) {
+            camera.toggle_projection();
+        }
+        
+        // T: cambiar el tipo de estrella (temperatura)
+        if rl.is_key_pressed(KeyboardKey::KEY_T) {
+            temperature_index = (temperature_index + 1) % STAR_TEMPERATURES.len();
+            uniforms.star_temperature = STAR_TEMPERATURES[temperature_index];
+        }
+        
+        // C: mostrar u ocultar la corona
+        if rl.is_key_pressed(KeyboardKey::KEY_C) {
+            show_corona = !show_corona;
+        }
+        
+        // V: mostrar u ocultar las protuberancias volumétricas
+        if rl.is_key_pressed(KeyboardKey::KEY_V) {
+            show_prominences = !show_prominences;
+        }
+        
+        // F / M: lanzar una fulguración o una eyección de masa coronal desde la mancha
+        // más grande (o desde el centro del disco si no hay manchas)
+        // En bucle no hay partículas: se ignoran para no acumular emisores sin dibujar
+        let flare = rl.is_key_pressed(KeyboardKey::KEY_F);
+        let ejection = rl.is_key_pressed(KeyboardKey::KEY_M);
+        if (flare || ejection) && time_loop.is_none() {
+            let origin = uniforms.sunspots
+                .iter()
+                .max_by(|a, b| a.radius.total_cmp(&b.radius))
+                .map_or(Vec3::Z, |spot| spot.center);
+            let (latitude, longitude) = Emitter::surface_coordinates(origin, time, &uniforms.differential_rotation);
+            particles.add_emitter(if flare {
+                Emitter::flare(latitude, longitude)
+            } else {
+                Emitter::coronal_mass_ejection(latitude, longitude)
+            });
+        }
+        
+        // N: nueva estrella (otra semilla para el ruido de la superficie)
+        if rl.is_key_pressed(KeyboardKey::KEY_N) {
+            star_seed += 1;
+            star_shader = StarShader::new(star_seed);
+            if baked_noise {
+                star_shader.bake(BAKE_RESOLUTION);
+            }
+        }
+        
+        // Q: alternar entre ruido exacto y horneado (se hornea la primera vez)
+        if rl.is_key_pressed(KeyboardKey::KEY_Q) {
+            baked_noise = !baked_noise;
+            if !baked_noise {
+                star_shader.baked = None;
+            } else {
+                star_shader.bake(BAKE_RESOLUTION);
+            }
+        }
+        
+        // B: alternar el sombreado en lotes SIMD y el de un fragmento a la vez
+        if rl.is_key_pressed(KeyboardKey::KEY_B) {
+            batched_shading = !batched_shading;
+        }
+        
+        // L: cambiar el modelo de oscurecimiento hacia el limbo
+        if rl.is_key_pressed(KeyboardKey::KEY_L) {
+            uniforms.limb_darkening = uniforms.limb_darkening.next();
+        }
+        
+        uniforms.view_matrix = camera.view_matrix();
+        uniforms.projection_matrix = camera.projection_matrix();
+        uniforms.camera_position = camera.position;
+        
         // Actualizar tiempo
-        time += rl.get_frame_time();
-        rotation_angle += 0.3 * rl.get_frame_time();
+        let frame_time = rl.get_frame_time();
+        elapsed += frame_time;
+        time = time_loop.map_or(elapsed, |time_loop| time_loop.wrap(elapsed));
         
         // Actualizar uniforms
         uniforms.time = time;
+        uniforms.sunspots = match time_loop {
+            None => sunspot_system.active_spots(time, &uniforms.differential_rotation),
+            Some(_) => loop_sunspots
+                .iter()
+                .map(|spot| Sunspot {
+                    center: uniforms.differential_rotation.surface_frame_to_object(spot.center, spot.center, time),
+                    ..*spot
+                })
+                .collect(),
+        };
+        // Las partículas son una simulación con estado: no se pueden repetir en bucle
+        if time_loop.is_none() {
+            particles.update(frame_time, time, &uniforms.differential_rotation);
+        }
         
-        // Crear matriz de modelo (rotación lenta)
-        let rotation_y = create_rotation_y_matrix(rotation_angle);
-        let rotation_x = create_rotation_x_matrix(rotation_angle * 0.5);
-        let scale = create_scale_matrix(1.5);
+        // Crear matriz de modelo: inclinación del eje y precesión
+        // (el giro sobre el eje propio depende de la latitud y lo aplica el fragment shader)
+        let precession = Quat::from_axis_angle(Vec3::Y, time * precession_speed);
+        star_transform.rotation = precession * axial_tilt;
         
-        uniforms.model_matrix = multiply_matrices(
-            &multiply_matrices(&rotation_y, &rotation_x),
-            &scale
-        );
+        uniforms.set_model_matrix(star_transform.to_matrix());
 
         // Limpiar framebuffer
         framebuffer.clear();
@@ -96,8 +383,28 @@ fn main() {
         // 1. Vertex Shader transforma los vértices
         // 2. Se ensamblan triángulos
         // 3. Se rasterizan (convierten a píxeles)
-        // 4. Fragment Shader calcula colores finales
-        render(&mut framebuffer, &uniforms, &vertex_array);
+        // 4. Fragment Shader calcula colores finales (de 4 en 4 con el ruido en lotes)
+        if batched_shading {
+            render_spans(&mut framebuffer, &uniforms, &star_shader, &vertex_array);
+        } else {
+            render(&mut framebuffer, &uniforms, &star_shader, &vertex_array);
+        }
+        
+        // Corona: se suma alrededor de la silueta después de dibujar la esfera
+        if show_corona {
+            let star_radius = mesh_radius * star_transform.scale.x;
+            corona.render(&mut framebuffer, &uniforms, star_transform.translation, star_radius);
+        }
+        
+        // Protuberancias: se integran sobre la esfera y la corona usando el buffer de profundidad
+        if show_prominences {
+            prominences.render(&mut framebuffer, &uniforms, mesh_radius);
+        }
+        
+        // Partículas al final: suman luz y solo se ocultan detrás de la esfera
+        if time_loop.is_none() {
+            particles.render(&mut framebuffer, &uniforms, mesh_radius);
+        }
 
         // Actualizar textura del framebuffer
         framebuffer.swap_buffers(&mut rl, &thread);
@@ -117,10 +424,54 @@ fn main() {
             20,
             Color::WHITE
         );
+        d.draw_text(
+            &format!("Temperatura: {:.0} K", uniforms.star_temperature),
+            10,
+            35,
+            20,
+            Color::WHITE
+        );
+        d.draw_text(
+            &format!("Limbo: {}", uniforms.limb_darkening.name()),
+            10,
+            60,
+            20,
+            Color::WHITE
+        );
+        d.draw_text(
+            &format!("Semilla: {}", star_shader.noise.seed()),
+            10,
+            85,
+            20,
+            Color::WHITE
+        );
+        d.draw_text(
+            &format!("Ruido: {}", if baked_noise { "horneado" } else { "exacto" }),
+            10,
+            110,
+            20,
+            Color::WHITE
+        );
+        d.draw_text(
+            &format!("Sombreado: {}", if batched_shading { "lotes SIMD" } else { "por fragmento" }),
+            10,
+            135,
+            20,
+            Color::WHITE
+        );
+        if let Some(time_loop) = &time_loop {
+            d.draw_text(
+                &format!("Bucle: {:.1} / {} s", time, time_loop.duration),
+                10,
+                160,
+                20,
+                Color::WHITE
+            );
+        }
         d.draw_text(
             "Estrella Animada con Shaders",
             10,
-            HEIGHT as i32 - 30,
+            framebuffer.height as i32 - 30,
             20,
             Color::WHITE
         );
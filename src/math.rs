@@ -0,0 +1,755 @@
+//! Tipos matemáticos del pipeline: vectores, matrices 4x4 y cuaterniones
+//! Las matrices se guardan por filas y multiplican vectores columna (M * v),
+//! igual que las matrices de transformación de `uniforms.rs`
+
+use raylib::prelude::*;
+use std::ops::{Add, AddAssign, Div, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Vector de 3 componentes
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Vec3 {
+    pub const ZERO: Vec3 = Vec3::new(0.0, 0.0, 0.0);
+    pub const ONE: Vec3 = Vec3::new(1.0, 1.0, 1.0);
+    pub const X: Vec3 = Vec3::new(1.0, 0.0, 0.0);
+    pub const Y: Vec3 = Vec3::new(0.0, 1.0, 0.0);
+    pub const Z: Vec3 = Vec3::new(0.0, 0.0, 1.0);
+
+    pub const fn new(x: f32, y: f32, z: f32) -> Self {
+        Self { x, y, z }
+    }
+
+    pub const fn splat(value: f32) -> Self {
+        Self::new(value, value, value)
+    }
+
+    pub fn dot(self, other: Vec3) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(self, other: Vec3) -> Vec3 {
+        Vec3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    pub fn length_squared(self) -> f32 {
+        self.dot(self)
+    }
+
+    pub fn length(self) -> f32 {
+        self.length_squared().sqrt()
+    }
+
+    /// Retorna el vector normalizado (un vector nulo se deja igual)
+    pub fn normalize(self) -> Vec3 {
+        let length = self.length();
+        if length > 0.0 {
+            self / length
+        } else {
+            self
+        }
+    }
+
+    pub fn lerp(self, other: Vec3, t: f32) -> Vec3 {
+        self + (other - self) * t
+    }
+
+    /// Extiende a Vec4 con la componente w indicada (1.0 = punto, 0.0 = dirección)
+    pub fn extend(self, w: f32) -> Vec4 {
+        Vec4::new(self.x, self.y, self.z, w)
+    }
+}
+
+/// Vector de 4 componentes (coordenadas homogéneas)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vec4 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Vec4 {
+    pub const ZERO: Vec4 = Vec4::new(0.0, 0.0, 0.0, 0.0);
+
+    pub const fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Self { x, y, z, w }
+    }
+
+    pub fn dot(self, other: Vec4) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    /// Descarta la componente w
+    pub fn truncate(self) -> Vec3 {
+        Vec3::new(self.x, self.y, self.z)
+    }
+
+    /// División de perspectiva (si w es 0 se retornan x, y, z sin dividir)
+    pub fn perspective_divide(self) -> Vec3 {
+        if self.w != 0.0 {
+            Vec3::new(self.x / self.w, self.y / self.w, self.z / self.w)
+        } else {
+            self.truncate()
+        }
+    }
+}
+
+/// Implementa los operadores componente a componente para un tipo vector
+macro_rules! impl_vector_ops {
+    ($t:ident, $($field:ident),+) => {
+        impl Add for $t {
+            type Output = $t;
+            fn add(self, other: $t) -> $t {
+                $t { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl Sub for $t {
+            type Output = $t;
+            fn sub(self, other: $t) -> $t {
+                $t { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl Mul for $t {
+            type Output = $t;
+            fn mul(self, other: $t) -> $t {
+                $t { $($field: self.$field * other.$field),+ }
+            }
+        }
+
+        impl Mul<f32> for $t {
+            type Output = $t;
+            fn mul(self, scalar: f32) -> $t {
+                $t { $($field: self.$field * scalar),+ }
+            }
+        }
+
+        impl Mul<$t> for f32 {
+            type Output = $t;
+            fn mul(self, vector: $t) -> $t {
+                vector * self
+            }
+        }
+
+        impl Div<f32> for $t {
+            type Output = $t;
+            fn div(self, scalar: f32) -> $t {
+                $t { $($field: self.$field / scalar),+ }
+            }
+        }
+
+        impl Neg for $t {
+            type Output = $t;
+            fn neg(self) -> $t {
+                $t { $($field: -self.$field),+ }
+            }
+        }
+
+        impl AddAssign for $t {
+            fn add_assign(&mut self, other: $t) {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $t {
+            fn sub_assign(&mut self, other: $t) {
+                *self = *self - other;
+            }
+        }
+
+        impl MulAssign<f32> for $t {
+            fn mul_assign(&mut self, scalar: f32) {
+                *self = *self * scalar;
+            }
+        }
+    };
+}
+
+impl_vector_ops!(Vec3, x, y, z);
+impl_vector_ops!(Vec4, x, y, z, w);
+
+impl From<Vector3> for Vec3 {
+    fn from(v: Vector3) -> Self {
+        Vec3::new(v.x, v.y, v.z)
+    }
+}
+
+impl From<Vec3> for Vector3 {
+    fn from(v: Vec3) -> Self {
+        Vector3::new(v.x, v.y, v.z)
+    }
+}
+
+/// Matriz 4x4 guardada por filas: `m[fila][columna]`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mat4 {
+    pub m: [[f32; 4]; 4],
+}
+
+impl Default for Mat4 {
+    fn default() -> Self {
+        Mat4::IDENTITY
+    }
+}
+
+impl Mat4 {
+    pub const IDENTITY: Mat4 = Mat4::from_rows([
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]);
+
+    pub const fn from_rows(m: [[f32; 4]; 4]) -> Self {
+        Self { m }
+    }
+
+    pub fn row(&self, i: usize) -> Vec4 {
+        Vec4::new(self.m[i][0], self.m[i][1], self.m[i][2], self.m[i][3])
+    }
+
+    pub fn column(&self, j: usize) -> Vec4 {
+        Vec4::new(self.m[0][j], self.m[1][j], self.m[2][j], self.m[3][j])
+    }
+
+    pub fn transpose(&self) -> Mat4 {
+        let mut result = [[0.0; 4]; 4];
+        for (i, row) in result.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = self.m[j][i];
+            }
+        }
+        Mat4::from_rows(result)
+    }
+
+    /// Cofactores 2x2 de las dos primeras y las dos últimas filas
+    /// (se reutilizan en el determinante y en la inversa)
+    fn sub_factors(&self) -> ([f32; 6], [f32; 6]) {
+        let m = &self.m;
+        let s = [
+            m[0][0] * m[1][1] - m[1][0] * m[0][1],
+            m[0][0] * m[1][2] - m[1][0] * m[0][2],
+            m[0][0] * m[1][3] - m[1][0] * m[0][3],
+            m[0][1] * m[1][2] - m[1][1] * m[0][2],
+            m[0][1] * m[1][3] - m[1][1] * m[0][3],
+            m[0][2] * m[1][3] - m[1][2] * m[0][3],
+        ];
+        let c = [
+            m[2][0] * m[3][1] - m[3][0] * m[2][1],
+            m[2][0] * m[3][2] - m[3][0] * m[2][2],
+            m[2][0] * m[3][3] - m[3][0] * m[2][3],
+            m[2][1] * m[3][2] - m[3][1] * m[2][2],
+            m[2][1] * m[3][3] - m[3][1] * m[2][3],
+            m[2][2] * m[3][3] - m[3][2] * m[2][3],
+        ];
+        (s, c)
+    }
+
+    pub fn determinant(&self) -> f32 {
+        let (s, c) = self.sub_factors();
+        s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0]
+    }
+
+    /// Inversa de la matriz; `None` si es singular
+    pub fn inverse(&self) -> Option<Mat4> {
+        let (s, c) = self.sub_factors();
+        let det = s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0];
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let inv_det = 1.0 / det;
+        let m = &self.m;
+
+        let result = [
+            [
+                (m[1][1] * c[5] - m[1][2] * c[4] + m[1][3] * c[3]) * inv_det,
+                (-m[0][1] * c[5] + m[0][2] * c[4] - m[0][3] * c[3]) * inv_det,
+                (m[3][1] * s[5] - m[3][2] * s[4] + m[3][3] * s[3]) * inv_det,
+                (-m[2][1] * s[5] + m[2][2] * s[4] - m[2][3] * s[3]) * inv_det,
+            ],
+            [
+                (-m[1][0] * c[5] + m[1][2] * c[2] - m[1][3] * c[1]) * inv_det,
+                (m[0][0] * c[5] - m[0][2] * c[2] + m[0][3] * c[1]) * inv_det,
+                (-m[3][0] * s[5] + m[3][2] * s[2] - m[3][3] * s[1]) * inv_det,
+                (m[2][0] * s[5] - m[2][2] * s[2] + m[2][3] * s[1]) * inv_det,
+            ],
+            [
+                (m[1][0] * c[4] - m[1][1] * c[2] + m[1][3] * c[0]) * inv_det,
+                (-m[0][0] * c[4] + m[0][1] * c[2] - m[0][3] * c[0]) * inv_det,
+                (m[3][0] * s[4] - m[3][1] * s[2] + m[3][3] * s[0]) * inv_det,
+                (-m[2][0] * s[4] + m[2][1] * s[2] - m[2][3] * s[0]) * inv_det,
+            ],
+            [
+                (-m[1][0] * c[3] + m[1][1] * c[1] - m[1][2] * c[0]) * inv_det,
+                (m[0][0] * c[3] - m[0][1] * c[1] + m[0][2] * c[0]) * inv_det,
+                (-m[3][0] * s[3] + m[3][1] * s[1] - m[3][2] * s[0]) * inv_det,
+                (m[2][0] * s[3] - m[2][1] * s[1] + m[2][2] * s[0]) * inv_det,
+            ],
+        ];
+
+        Some(Mat4::from_rows(result))
+    }
+
+    /// Transforma un punto (w = 1) y aplica la división de perspectiva
+    pub fn transform_point(&self, point: Vec3) -> Vec3 {
+        (*self * point.extend(1.0)).perspective_divide()
+    }
+
+    /// Transforma una dirección (w = 0): ignora la traslación
+    pub fn transform_vector(&self, vector: Vec3) -> Vec3 {
+        (*self * vector.extend(0.0)).truncate()
+    }
+}
+
+impl Mul for Mat4 {
+    type Output = Mat4;
+    fn mul(self, other: Mat4) -> Mat4 {
+        let mut result = [[0.0; 4]; 4];
+        for (i, row) in result.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                for k in 0..4 {
+                    *value += self.m[i][k] * other.m[k][j];
+                }
+            }
+        }
+        Mat4::from_rows(result)
+    }
+}
+
+impl Mul<Vec4> for Mat4 {
+    type Output = Vec4;
+    fn mul(self, v: Vec4) -> Vec4 {
+        Vec4::new(
+            self.row(0).dot(v),
+            self.row(1).dot(v),
+            self.row(2).dot(v),
+            self.row(3).dot(v),
+        )
+    }
+}
+
+impl MulAssign for Mat4 {
+    fn mul_assign(&mut self, other: Mat4) {
+        *self = *self * other;
+    }
+}
+
+impl Index<usize> for Mat4 {
+    type Output = [f32; 4];
+    fn index(&self, row: usize) -> &[f32; 4] {
+        &self.m[row]
+    }
+}
+
+impl IndexMut<usize> for Mat4 {
+    fn index_mut(&mut self, row: usize) -> &mut [f32; 4] {
+        &mut self.m[row]
+    }
+}
+
+impl From<[[f32; 4]; 4]> for Mat4 {
+    fn from(m: [[f32; 4]; 4]) -> Self {
+        Mat4::from_rows(m)
+    }
+}
+
+impl From<Mat4> for [[f32; 4]; 4] {
+    fn from(matrix: Mat4) -> Self {
+        matrix.m
+    }
+}
+
+/// Orden en que se aplican las rotaciones de los ángulos de Euler
+/// `Xyz` = primero alrededor de X, luego de Y y por último de Z (ejes fijos del mundo)
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EulerOrder {
+    Xyz,
+    Xzy,
+    Yxz,
+    Yzx,
+    Zxy,
+    Zyx,
+}
+
+/// Cuaternión unitario para representar rotaciones (x, y, z = parte vectorial, w = escalar)
+/// Las rotaciones siguen la regla de la mano derecha (ángulo positivo = antihorario)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quat {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Default for Quat {
+    fn default() -> Self {
+        Quat::IDENTITY
+    }
+}
+
+impl Quat {
+    pub const IDENTITY: Quat = Quat::new(0.0, 0.0, 0.0, 1.0);
+
+    pub const fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Self { x, y, z, w }
+    }
+
+    /// Rotación de `angle` radianes alrededor de `axis` (no necesita estar normalizado)
+    pub fn from_axis_angle(axis: Vec3, angle: f32) -> Quat {
+        let axis = axis.normalize();
+        let (sin, cos) = (angle * 0.5).sin_cos();
+        Quat::new(axis.x * sin, axis.y * sin, axis.z * sin, cos)
+    }
+
+    /// Rotación a partir de ángulos de Euler `(x, y, z)` aplicados en el orden indicado
+    #[allow(dead_code)]
+    pub fn from_euler(order: EulerOrder, x: f32, y: f32, z: f32) -> Quat {
+        let qx = Quat::from_axis_angle(Vec3::X, x);
+        let qy = Quat::from_axis_angle(Vec3::Y, y);
+        let qz = Quat::from_axis_angle(Vec3::Z, z);
+
+        // La primera rotación aplicada queda a la derecha del producto
+        match order {
+            EulerOrder::Xyz => qz * qy * qx,
+            EulerOrder::Xzy => qy * qz * qx,
+            EulerOrder::Yxz => qz * qx * qy,
+            EulerOrder::Yzx => qx * qz * qy,
+            EulerOrder::Zxy => qy * qx * qz,
+            EulerOrder::Zyx => qx * qy * qz,
+        }
+    }
+
+    /// Extrae la rotación de la parte 3x3 de una matriz (sin escala ni sesgo)
+    #[allow(dead_code)]
+    pub fn from_matrix(matrix: &Mat4) -> Quat {
+        let m = &matrix.m;
+        let trace = m[0][0] + m[1][1] + m[2][2];
+
+        // Se elige la fórmula con el denominador más grande para evitar inestabilidad numérica
+        let q = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Quat::new(
+                (m[2][1] - m[1][2]) / s,
+                (m[0][2] - m[2][0]) / s,
+                (m[1][0] - m[0][1]) / s,
+                0.25 * s,
+            )
+        } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+            let s = (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt() * 2.0;
+            Quat::new(
+                0.25 * s,
+                (m[0][1] + m[1][0]) / s,
+                (m[0][2] + m[2][0]) / s,
+                (m[2][1] - m[1][2]) / s,
+            )
+        } else if m[1][1] > m[2][2] {
+            let s = (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt() * 2.0;
+            Quat::new(
+                (m[0][1] + m[1][0]) / s,
+                0.25 * s,
+                (m[1][2] + m[2][1]) / s,
+                (m[0][2] - m[2][0]) / s,
+            )
+        } else {
+            let s = (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt() * 2.0;
+            Quat::new(
+                (m[0][2] + m[2][0]) / s,
+                (m[1][2] + m[2][1]) / s,
+                0.25 * s,
+                (m[1][0] - m[0][1]) / s,
+            )
+        };
+
+        q.normalize()
+    }
+
+    /// Matriz de rotación equivalente
+    pub fn to_matrix(self) -> Mat4 {
+        let Quat { x, y, z, w } = self.normalize();
+        let (xx, yy, zz) = (x * x, y * y, z * z);
+        let (xy, xz, yz) = (x * y, x * z, y * z);
+        let (wx, wy, wz) = (w * x, w * y, w * z);
+
+        Mat4::from_rows([
+            [1.0 - 2.0 * (yy + zz), 2.0 * (xy - wz), 2.0 * (xz + wy), 0.0],
+            [2.0 * (xy + wz), 1.0 - 2.0 * (xx + zz), 2.0 * (yz - wx), 0.0],
+            [2.0 * (xz - wy), 2.0 * (yz + wx), 1.0 - 2.0 * (xx + yy), 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// Interpolación esférica: velocidad angular constante entre dos orientaciones
+    /// Siempre toma el camino más corto
+    #[allow(dead_code)]
+    pub fn slerp(self, other: Quat, t: f32) -> Quat {
+        let mut other = other;
+        let mut cos_theta = self.dot(other);
+
+        // q y -q representan la misma rotación: elegir el hemisferio más cercano
+        if cos_theta < 0.0 {
+            other = Quat::new(-other.x, -other.y, -other.z, -other.w);
+            cos_theta = -cos_theta;
+        }
+
+        // Con ángulos muy pequeños slerp degenera: basta con interpolar linealmente
+        let (a, b) = if cos_theta > 0.9995 {
+            (1.0 - t, t)
+        } else {
+            let theta = cos_theta.acos();
+            let sin_theta = theta.sin();
+            (((1.0 - t) * theta).sin() / sin_theta, (t * theta).sin() / sin_theta)
+        };
+
+        Quat::new(
+            a * self.x + b * other.x,
+            a * self.y + b * other.y,
+            a * self.z + b * other.z,
+            a * self.w + b * other.w,
+        )
+        .normalize()
+    }
+
+    pub fn dot(self, other: Quat) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    pub fn length(self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalize(self) -> Quat {
+        let length = self.length();
+        if length > 0.0 {
+            Quat::new(self.x / length, self.y / length, self.z / length, self.w / length)
+        } else {
+            Quat::IDENTITY
+        }
+    }
+
+    /// Conjugado: para cuaterniones unitarios es la rotación inversa
+    #[allow(dead_code)]
+    pub fn conjugate(self) -> Quat {
+        Quat::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    #[allow(dead_code)]
+    pub fn inverse(self) -> Quat {
+        let length_squared = self.dot(self);
+        if length_squared > 0.0 {
+            let c = self.conjugate();
+            Quat::new(c.x / length_squared, c.y / length_squared, c.z / length_squared, c.w / length_squared)
+        } else {
+            Quat::IDENTITY
+        }
+    }
+
+    /// Rota un vector: q * v * q⁻¹ (forma optimizada para cuaterniones unitarios)
+    pub fn rotate(self, v: Vec3) -> Vec3 {
+        let u = Vec3::new(self.x, self.y, self.z);
+        let t = u.cross(v) * 2.0;
+        v + t * self.w + u.cross(t)
+    }
+}
+
+/// Composición de rotaciones: `a * b` aplica primero `b` y luego `a`
+impl Mul for Quat {
+    type Output = Quat;
+    fn mul(self, o: Quat) -> Quat {
+        Quat::new(
+            self.w * o.x + self.x * o.w + self.y * o.z - self.z * o.y,
+            self.w * o.y - self.x * o.z + self.y * o.w + self.z * o.x,
+            self.w * o.z + self.x * o.y - self.y * o.x + self.z * o.w,
+            self.w * o.w - self.x * o.x - self.y * o.y - self.z * o.z,
+        )
+    }
+}
+
+impl Mul<Vec3> for Quat {
+    type Output = Vec3;
+    fn mul(self, v: Vec3) -> Vec3 {
+        self.rotate(v)
+    }
+}
+
+/// Interpolación de Hermite entre 0 y 1 cuando `x` va de `edge0` a `edge1` (como en GLSL)
+pub fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Derivada de `smoothstep` respecto a `x` (0 fuera del intervalo)
+pub fn smoothstep_derivative(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = (x - edge0) / (edge1 - edge0);
+    if (0.0..=1.0).contains(&t) { 6.0 * t * (1.0 - t) / (edge1 - edge0) } else { 0.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-5;
+
+    fn assert_vec3_eq(a: Vec3, b: Vec3) {
+        assert!((a - b).length() < EPSILON, "{:?} != {:?}", a, b);
+    }
+
+    fn assert_mat4_eq(a: &Mat4, b: &Mat4) {
+        for i in 0..4 {
+            for j in 0..4 {
+                assert!((a[i][j] - b[i][j]).abs() < EPSILON, "{:?} != {:?}", a, b);
+            }
+        }
+    }
+
+    fn sample_matrix() -> Mat4 {
+        Mat4::from_rows([
+            [2.0, 0.5, 0.0, 1.0],
+            [0.0, 1.5, -0.3, 2.0],
+            [0.4, 0.0, 3.0, -1.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    #[test]
+    fn vec3_operators() {
+        let a = Vec3::new(1.0, 2.0, 3.0);
+        let b = Vec3::new(4.0, -5.0, 6.0);
+        assert_eq!(a + b, Vec3::new(5.0, -3.0, 9.0));
+        assert_eq!(a - b, Vec3::new(-3.0, 7.0, -3.0));
+        assert_eq!(a * 2.0, Vec3::new(2.0, 4.0, 6.0));
+        assert_eq!(2.0 * a, a * 2.0);
+        assert_eq!(-a, Vec3::new(-1.0, -2.0, -3.0));
+        assert_eq!(a.dot(b), 12.0);
+        assert_vec3_eq(Vec3::X.cross(Vec3::Y), Vec3::Z);
+        assert!((b.normalize().length() - 1.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn vector3_conversions_round_trip() {
+        let v = Vec3::new(0.5, -1.5, 2.5);
+        let raylib: Vector3 = v.into();
+        assert_eq!(Vec3::from(raylib), v);
+    }
+
+    #[test]
+    fn identity_is_neutral() {
+        let m = sample_matrix();
+        assert_mat4_eq(&(m * Mat4::IDENTITY), &m);
+        assert_mat4_eq(&(Mat4::IDENTITY * m), &m);
+    }
+
+    #[test]
+    fn transpose_swaps_rows_and_columns() {
+        let m = sample_matrix();
+        let t = m.transpose();
+        for i in 0..4 {
+            assert_eq!(m.row(i), t.column(i));
+        }
+        assert_mat4_eq(&t.transpose(), &m);
+    }
+
+    #[test]
+    fn determinant_of_known_matrices() {
+        assert!((Mat4::IDENTITY.determinant() - 1.0).abs() < EPSILON);
+        let scale = Mat4::from_rows([
+            [2.0, 0.0, 0.0, 0.0],
+            [0.0, 3.0, 0.0, 0.0],
+            [0.0, 0.0, 4.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+        assert!((scale.determinant() - 24.0).abs() < EPSILON);
+        let m = sample_matrix();
+        assert!((m.determinant() - m.transpose().determinant()).abs() < EPSILON);
+    }
+
+    #[test]
+    fn inverse_undoes_matrix() {
+        let m = sample_matrix();
+        let inverse = m.inverse().expect("la matriz debe ser invertible");
+        assert_mat4_eq(&(m * inverse), &Mat4::IDENTITY);
+        assert_mat4_eq(&(inverse * m), &Mat4::IDENTITY);
+
+        let p = Vec3::new(0.3, -2.0, 5.0);
+        assert_vec3_eq(inverse.transform_point(m.transform_point(p)), p);
+    }
+
+    #[test]
+    fn singular_matrix_has_no_inverse() {
+        let mut m = sample_matrix();
+        m[1] = m[0];
+        assert!(m.inverse().is_none());
+    }
+
+    #[test]
+    fn transform_vector_ignores_translation() {
+        let m = sample_matrix();
+        assert_vec3_eq(m.transform_vector(Vec3::ZERO), Vec3::ZERO);
+        assert_vec3_eq(m.transform_point(Vec3::ZERO), Vec3::new(1.0, 2.0, -1.0));
+    }
+
+    #[test]
+    fn quaternion_rotates_about_axis() {
+        let q = Quat::from_axis_angle(Vec3::Z, std::f32::consts::FRAC_PI_2);
+        assert_vec3_eq(q * Vec3::X, Vec3::Y);
+        assert_vec3_eq(q.conjugate() * (q * Vec3::new(1.0, 2.0, 3.0)), Vec3::new(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn quaternion_matrix_round_trip() {
+        let q = Quat::from_axis_angle(Vec3::new(0.3, -1.0, 0.6), 2.4);
+        let m = q.to_matrix();
+        let v = Vec3::new(1.0, 2.0, -0.5);
+        assert_vec3_eq(m.transform_vector(v), q * v);
+
+        let back = Quat::from_matrix(&m);
+        assert!(back.dot(q).abs() > 1.0 - EPSILON);
+    }
+
+    #[test]
+    fn euler_order_matches_sequential_rotations() {
+        let (x, y, z) = (0.4, -1.1, 0.9);
+        let v = Vec3::new(0.2, 0.7, -0.4);
+        let qx = Quat::from_axis_angle(Vec3::X, x);
+        let qy = Quat::from_axis_angle(Vec3::Y, y);
+        let qz = Quat::from_axis_angle(Vec3::Z, z);
+
+        assert_vec3_eq(Quat::from_euler(EulerOrder::Xyz, x, y, z) * v, qz * (qy * (qx * v)));
+        assert_vec3_eq(Quat::from_euler(EulerOrder::Zyx, x, y, z) * v, qx * (qy * (qz * v)));
+        assert_vec3_eq(Quat::from_euler(EulerOrder::Yxz, x, y, z) * v, qz * (qx * (qy * v)));
+    }
+
+    #[test]
+    fn slerp_interpolates_angle_linearly() {
+        let a = Quat::IDENTITY;
+        let b = Quat::from_axis_angle(Vec3::Y, 1.2);
+        assert!(a.slerp(b, 0.0).dot(a).abs() > 1.0 - EPSILON);
+        assert!(a.slerp(b, 1.0).dot(b).abs() > 1.0 - EPSILON);
+
+        let half = a.slerp(b, 0.5);
+        assert!(half.dot(Quat::from_axis_angle(Vec3::Y, 0.6)).abs() > 1.0 - EPSILON);
+
+        // Con el cuaternión negado debe tomar el mismo camino corto
+        let negated = Quat::new(-b.x, -b.y, -b.z, -b.w);
+        assert!(a.slerp(negated, 0.5).dot(half).abs() > 1.0 - EPSILON);
+    }
+
+    #[test]
+    fn quaternion_composition_applies_right_first() {
+        let a = Quat::from_axis_angle(Vec3::Z, std::f32::consts::FRAC_PI_2);
+        let b = Quat::from_axis_angle(Vec3::X, std::f32::consts::FRAC_PI_2);
+        let v = Vec3::new(0.2, 0.7, -0.4);
+        assert_vec3_eq((a * b) * v, a * (b * v));
+        assert_vec3_eq((a * a.inverse()) * v, v);
+    }
+}
@@ -1,4 +1,24 @@
+mod simplex;
+mod cellular;
+mod fractal;
+mod curl;
+mod periodic;
+mod volume;
+mod simd;
+#[cfg(test)]
+pub(crate) mod test_points;
+
+pub use cellular::{CellularSample, DistanceMetric};
+pub use fractal::{Basis, Fractal};
+pub use curl::CurlField;
+pub use periodic::TimeLoop;
+pub use volume::BakedNoise;
+pub use simd::{CellularBatch, F32x4, Simd, Vec3Batch};
+
+use std::sync::OnceLock;
 use raylib::prelude::*;
+use crate::math::Vec3;
+use crate::random::Rng;
 
 /// Implementación de Perlin Noise para generar turbulencia en la estrella
 /// El ruido de Perlin es una función de ruido de gradiente que produce
@@ -9,129 +29,333 @@ fn fade(t: f32) -> f32 {
     t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
 }
 
+/// Derivada de `fade`
+fn fade_derivative(t: f32) -> f32 {
+    30.0 * t * t * (t * (t - 2.0) + 1.0)
+}
+
 /// Interpolación lineal
 fn lerp(a: f32, b: f32, t: f32) -> f32 {
     a + t * (b - a)
 }
 
-/// Función de hash simple para generar valores pseudoaleatorios
-fn hash(x: i32, y: i32, z: i32) -> f32 {
-    let n = x.wrapping_add(y.wrapping_mul(57)).wrapping_add(z.wrapping_mul(113));
-    let n = (n << 13) ^ n;
-    let n = (n.wrapping_mul(n.wrapping_mul(n).wrapping_mul(15731).wrapping_add(789221)).wrapping_add(1376312589)) & 0x7fffffff;
-    1.0 - (n as f32 / 1073741824.0)
+/// Gradientes del ruido de Perlin mejorado (2002): los 12 vectores que van del centro a
+/// las aristas de un cubo, más 4 repetidos para poder elegir con `hash & 15` sin sesgo
+/// notable. Al no haber gradientes alineados con los ejes no aparecen artefactos en cruz
+const GRADIENTS: [[f32; 3]; 16] = [
+    [1.0, 1.0, 0.0], [-1.0, 1.0, 0.0], [1.0, -1.0, 0.0], [-1.0, -1.0, 0.0],
+    [1.0, 0.0, 1.0], [-1.0, 0.0, 1.0], [1.0, 0.0, -1.0], [-1.0, 0.0, -1.0],
+    [0.0, 1.0, 1.0], [0.0, -1.0, 1.0], [0.0, 1.0, -1.0], [0.0, -1.0, -1.0],
+    [1.0, 1.0, 0.0], [-1.0, 1.0, 0.0], [0.0, -1.0, 1.0], [0.0, -1.0, -1.0],
+];
+
+/// Gradiente elegido por `hash` como vector
+fn gradient(hash: u8) -> Vec3 {
+    let g = GRADIENTS[(hash & 15) as usize];
+    Vec3::new(g[0], g[1], g[2])
 }
 
-/// Producto punto de gradiente
-fn grad(hash: i32, x: f32, y: f32, z: f32) -> f32 {
-    let h = hash & 15;
-    let u = if h < 8 { x } else { y };
-    let v = if h < 4 { y } else if h == 12 || h == 14 { x } else { z };
-    
-    let u_val = if (h & 1) == 0 { u } else { -u };
-    let v_val = if (h & 2) == 0 { v } else { -v };
-    
-    u_val + v_val
+/// Producto punto entre el gradiente elegido por `hash` y el vector a la esquina
+fn grad(hash: u8, x: f32, y: f32, z: f32) -> f32 {
+    let g = GRADIENTS[(hash & 15) as usize];
+    g[0] * x + g[1] * y + g[2] * z
 }
 
-/// Perlin Noise 3D - genera ruido suave y coherente
+/// Generador de ruido con semilla
+/// La semilla baraja una tabla de permutación de 256 entradas: la misma semilla produce
+/// siempre el mismo ruido y semillas distintas producen estrellas distintas
+#[derive(Clone, Debug)]
+pub struct NoiseGenerator {
+    seed: u64,
+    permutation: [u8; 512], // Tabla duplicada para no tener que envolver los índices
+}
+
+impl NoiseGenerator {
+    /// Semilla del generador que usan las funciones libres del módulo
+    pub const DEFAULT_SEED: u64 = 0;
+
+    pub fn new(seed: u64) -> Self {
+        // Barajado de Fisher-Yates de 0..=255
+        let mut table: [u8; 256] = std::array::from_fn(|i| i as u8);
+        let mut rng = Rng::new(seed);
+        for i in (1..table.len()).rev() {
+            let j = rng.below(i as u32 + 1) as usize;
+            table.swap(i, j);
+        }
+
+        let mut permutation = [0u8; 512];
+        for (i, value) in permutation.iter_mut().enumerate() {
+            *value = table[i & 255];
+        }
+        Self { seed, permutation }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Entero pseudoaleatorio en [0, 255] para una celda entera, a partir de la permutación
+    fn hash_index(&self, x: i32, y: i32, z: i32) -> u8 {
+        let p = &self.permutation;
+        p[p[p[(x & 255) as usize] as usize + (y & 255) as usize] as usize + (z & 255) as usize]
+    }
+
+    /// Perlin Noise 3D - genera ruido suave y coherente
+    /// Salida en [0, 1] con media 0.5 y desviación típica ~0.14: el ruido "crudo" está en
+    /// [-1, 1] (el 98% de las veces en [-0.6, 0.6]) y se lleva a [0, 1] con (n + 1) / 2
+    /// Con varias octavas la media se suma: ~0.875 con 3 y ~0.94 con 4
+    pub fn perlin(&self, x: f32, y: f32, z: f32) -> f32 {
+        // Encontrar la celda unitaria que contiene el punto
+        let xi = x.floor() as i32 & 255;
+        let yi = y.floor() as i32 & 255;
+        let zi = z.floor() as i32 & 255;
+
+        // Encontrar posición relativa del punto en la celda
+        let xf = x - x.floor();
+        let yf = y - y.floor();
+        let zf = z - z.floor();
+
+        // Calcular curvas de fade
+        let u = fade(xf);
+        let v = fade(yf);
+        let w = fade(zf);
+
+        // Hash de las coordenadas de las 8 esquinas del cubo
+        let aaa = self.hash_index(xi, yi, zi);
+        let aba = self.hash_index(xi, yi + 1, zi);
+        let aab = self.hash_index(xi, yi, zi + 1);
+        let abb = self.hash_index(xi, yi + 1, zi + 1);
+        let baa = self.hash_index(xi + 1, yi, zi);
+        let bba = self.hash_index(xi + 1, yi + 1, zi);
+        let bab = self.hash_index(xi + 1, yi, zi + 1);
+        let bbb = self.hash_index(xi + 1, yi + 1, zi + 1);
+
+        // Interpolar a lo largo de x
+        let x1 = lerp(grad(aaa, xf, yf, zf), grad(baa, xf - 1.0, yf, zf), u);
+        let x2 = lerp(grad(aba, xf, yf - 1.0, zf), grad(bba, xf - 1.0, yf - 1.0, zf), u);
+        let y1 = lerp(x1, x2, v);
+
+        let x3 = lerp(grad(aab, xf, yf, zf - 1.0), grad(bab, xf - 1.0, yf, zf - 1.0), u);
+        let x4 = lerp(grad(abb, xf, yf - 1.0, zf - 1.0), grad(bbb, xf - 1.0, yf - 1.0, zf - 1.0), u);
+        let y2 = lerp(x3, x4, v);
+
+        // Interpolar a lo largo de z
+        ((lerp(y1, y2, w) + 1.0) / 2.0).clamp(0.0, 1.0)
+    }
+
+    /// Perlin 3D con su gradiente analítico: (valor en [0, 1], ∂valor/∂p)
+    /// Cuesta casi lo mismo que `perlin` y evita las 3-6 evaluaciones extra de las
+    /// diferencias finitas (por ejemplo, para perturbar normales)
+    pub fn perlin_derivative(&self, x: f32, y: f32, z: f32) -> (f32, Vec3) {
+        let (xi, yi, zi) = (x.floor() as i32 & 255, y.floor() as i32 & 255, z.floor() as i32 & 255);
+        let f = Vec3::new(x - x.floor(), y - y.floor(), z - z.floor());
+        let u = Vec3::new(fade(f.x), fade(f.y), fade(f.z));
+        let du = Vec3::new(fade_derivative(f.x), fade_derivative(f.y), fade_derivative(f.z));
+
+        // Gradientes y productos punto de las 8 esquinas (a = 000, b = 100, c = 010,
+        // d = 110, e = 001, f = 101, g = 011, h = 111)
+        let corner = |dx: i32, dy: i32, dz: i32| {
+            let g = gradient(self.hash_index(xi + dx, yi + dy, zi + dz));
+            (g, g.dot(f - Vec3::new(dx as f32, dy as f32, dz as f32)))
+        };
+        let (ga, va) = corner(0, 0, 0);
+        let (gb, vb) = corner(1, 0, 0);
+        let (gc, vc) = corner(0, 1, 0);
+        let (gd, vd) = corner(1, 1, 0);
+        let (ge, ve) = corner(0, 0, 1);
+        let (gf, vf) = corner(1, 0, 1);
+        let (gg, vg) = corner(0, 1, 1);
+        let (gh, vh) = corner(1, 1, 1);
+
+        // La interpolación trilineal escrita como polinomio en u, v, w
+        let (k1, k2, k3) = (vb - va, vc - va, ve - va);
+        let (k4, k5, k6) = (va - vb - vc + vd, va - vc - ve + vg, va - vb - ve + vf);
+        let k7 = -va + vb + vc - vd + ve - vf - vg + vh;
+        let value = va + u.x * k1 + u.y * k2 + u.z * k3
+            + u.x * u.y * k4 + u.y * u.z * k5 + u.z * u.x * k6
+            + u.x * u.y * u.z * k7;
+
+        // Derivada: la de los gradientes interpolados más la de los pesos de fade
+        let gradient_term = ga + (gb - ga) * u.x + (gc - ga) * u.y + (ge - ga) * u.z
+            + (ga - gb - gc + gd) * (u.x * u.y)
+            + (ga - gc - ge + gg) * (u.y * u.z)
+            + (ga - gb - ge + gf) * (u.z * u.x)
+            + (-ga + gb + gc - gd + ge - gf - gg + gh) * (u.x * u.y * u.z);
+        let weight_term = du * Vec3::new(
+            k1 + u.y * k4 + u.z * k6 + u.y * u.z * k7,
+            k2 + u.z * k5 + u.x * k4 + u.z * u.x * k7,
+            k3 + u.x * k6 + u.y * k5 + u.x * u.y * k7,
+        );
+
+        // Misma conversión a [0, 1] que `perlin`: el gradiente se escala a la mitad
+        (((value + 1.0) * 0.5).clamp(0.0, 1.0), (gradient_term + weight_term) * 0.5)
+    }
+
+    /// Turbulencia - combina múltiples octavas de ruido para crear patrones complejos
+    #[allow(dead_code)]
+    pub fn turbulence(&self, x: f32, y: f32, z: f32, octaves: i32) -> f32 {
+        let mut value = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+
+        for _ in 0..octaves {
+            value += amplitude * self.perlin(x * frequency, y * frequency, z * frequency);
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+
+        value
+    }
+}
+
+impl Default for NoiseGenerator {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SEED)
+    }
+}
+
+/// Generador compartido por las funciones libres (semilla por defecto)
+pub fn default_generator() -> &'static NoiseGenerator {
+    static GENERATOR: OnceLock<NoiseGenerator> = OnceLock::new();
+    GENERATOR.get_or_init(NoiseGenerator::default)
+}
+
+/// Perlin Noise 3D con el generador por defecto
 pub fn perlin_noise(x: f32, y: f32, z: f32) -> f32 {
-    // Encontrar la celda unitaria que contiene el punto
-    let xi = x.floor() as i32 & 255;
-    let yi = y.floor() as i32 & 255;
-    let zi = z.floor() as i32 & 255;
-    
-    // Encontrar posición relativa del punto en la celda
-    let xf = x - x.floor();
-    let yf = y - y.floor();
-    let zf = z - z.floor();
-    
-    // Calcular curvas de fade
-    let u = fade(xf);
-    let v = fade(yf);
-    let w = fade(zf);
-    
-    // Hash de las coordenadas de las 8 esquinas del cubo
-    let aaa = hash(xi, yi, zi);
-    let aba = hash(xi, yi + 1, zi);
-    let aab = hash(xi, yi, zi + 1);
-    let abb = hash(xi, yi + 1, zi + 1);
-    let baa = hash(xi + 1, yi, zi);
-    let bba = hash(xi + 1, yi + 1, zi);
-    let bab = hash(xi + 1, yi, zi + 1);
-    let bbb = hash(xi + 1, yi + 1, zi + 1);
-    
-    // Interpolar a lo largo de x
-    let x1 = lerp(grad(aaa as i32, xf, yf, zf) as f32, grad(baa as i32, xf - 1.0, yf, zf) as f32, u);
-    let x2 = lerp(grad(aba as i32, xf, yf - 1.0, zf) as f32, grad(bba as i32, xf - 1.0, yf - 1.0, zf) as f32, u);
-    let y1 = lerp(x1, x2, v);
-    
-    let x3 = lerp(grad(aab as i32, xf, yf, zf - 1.0) as f32, grad(bab as i32, xf - 1.0, yf, zf - 1.0) as f32, u);
-    let x4 = lerp(grad(abb as i32, xf, yf - 1.0, zf - 1.0) as f32, grad(bbb as i32, xf - 1.0, yf - 1.0, zf - 1.0) as f32, u);
-    let y2 = lerp(x3, x4, v);
-    
-    // Interpolar a lo largo de z
-    (lerp(y1, y2, w) + 1.0) / 2.0
+    default_generator().perlin(x, y, z)
 }
 
-/// Turbulencia - combina múltiples octavas de ruido para crear patrones complejos
+/// Turbulencia con el generador por defecto
+#[allow(dead_code)]
 pub fn turbulence(x: f32, y: f32, z: f32, octaves: i32) -> f32 {
-    let mut value = 0.0;
-    let mut amplitude = 1.0;
-    let mut frequency = 1.0;
-    
-    for _ in 0..octaves {
-        value += amplitude * perlin_noise(x * frequency, y * frequency, z * frequency);
-        amplitude *= 0.5;
-        frequency *= 2.0;
-    }
-    
-    value
+    default_generator().turbulence(x, y, z, octaves)
 }
 
-/// Cellular/Worley Noise - crea patrones celulares
+/// Cellular/Worley Noise con el generador por defecto
+#[allow(dead_code)]
 pub fn cellular_noise(pos: Vector3, scale: f32) -> f32 {
-    let p = Vector3::new(pos.x * scale, pos.y * scale, pos.z * scale);
-    
-    let cell_x = p.x.floor();
-    let cell_y = p.y.floor();
-    let cell_z = p.z.floor();
-    
-    let mut min_dist: f32 = 10000.0;
-    
-    // Buscar en las celdas vecinas
-    for i in -1..=1 {
-        for j in -1..=1 {
-            for k in -1..=1 {
-                let neighbor_x = cell_x + i as f32;
-                let neighbor_y = cell_y + j as f32;
-                let neighbor_z = cell_z + k as f32;
-                
-                // Generar punto de característica pseudoaleatorio
-                let px = neighbor_x + hash(neighbor_x as i32, neighbor_y as i32, neighbor_z as i32) * 0.5 + 0.25;
-                let py = neighbor_y + hash(neighbor_y as i32, neighbor_z as i32, neighbor_x as i32) * 0.5 + 0.25;
-                let pz = neighbor_z + hash(neighbor_z as i32, neighbor_x as i32, neighbor_y as i32) * 0.5 + 0.25;
-                
-                // Calcular distancia al punto de característica
-                let dx = p.x - px;
-                let dy = p.y - py;
-                let dz = p.z - pz;
-                let dist = (dx * dx + dy * dy + dz * dz).sqrt();
-                
-                min_dist = min_dist.min(dist);
-            }
-        }
-    }
-    
-    min_dist
+    default_generator().cellular(pos.into(), scale)
 }
 
-/// Simplex Noise simplificado - alternativa más eficiente a Perlin
+/// Simplex Noise 4D con el generador por defecto: el tiempo es la cuarta dimensión
+#[allow(dead_code)]
 pub fn simplex_noise(pos: Vector3, time: f32) -> f32 {
-    // Versión simplificada usando Perlin como base
-    let noise1 = perlin_noise(pos.x + time * 0.1, pos.y, pos.z);
-    let noise2 = perlin_noise(pos.x, pos.y + time * 0.15, pos.z + time * 0.1);
-    let noise3 = perlin_noise(pos.x - time * 0.08, pos.y - time * 0.12, pos.z);
-    
-    (noise1 + noise2 + noise3) / 3.0
+    default_generator().simplex4(pos.x, pos.y, pos.z, time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_points::points;
+
+    #[test]
+    fn same_seed_same_noise() {
+        let a = NoiseGenerator::new(99);
+        let b = NoiseGenerator::new(99);
+        let c = NoiseGenerator::new(100);
+        let mut differs = false;
+        for i in 0..100 {
+            let (x, y, z) = (i as f32 * 0.73, i as f32 * 0.31 - 4.0, i as f32 * 1.17);
+            assert_eq!(a.perlin(x, y, z), b.perlin(x, y, z));
+            assert_eq!(a.turbulence(x, y, z, 3), b.turbulence(x, y, z, 3));
+            differs |= a.cellular(Vec3::new(x, y, z), 1.0) != c.cellular(Vec3::new(x, y, z), 1.0);
+        }
+        assert!(differs);
+    }
+
+    #[test]
+    fn permutation_is_a_shuffle() {
+        let generator = NoiseGenerator::new(7);
+        let mut seen = [false; 256];
+        for &value in &generator.permutation[..256] {
+            seen[value as usize] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+        assert_eq!(generator.permutation[..256], generator.permutation[256..]);
+    }
+
+    /// Muestras de ruido en los puntos de prueba compartidos
+    fn samples(generator: &NoiseGenerator, count: usize) -> Vec<(Vec3, f32)> {
+        points(count).map(|p| (p, generator.perlin(p.x, p.y, p.z))).collect()
+    }
+
+    #[test]
+    fn perlin_statistics() {
+        let generator = NoiseGenerator::new(3);
+        let values: Vec<f32> = samples(&generator, 20_000).into_iter().map(|(_, v)| v).collect();
+        let n = values.len() as f32;
+        let mean = values.iter().sum::<f32>() / n;
+        let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f32>() / n;
+        let min = values.iter().copied().fold(f32::MAX, f32::min);
+        let max = values.iter().copied().fold(f32::MIN, f32::max);
+
+        assert!((mean - 0.5).abs() < 0.01, "media {mean}");
+        assert!((0.1..0.17).contains(&variance.sqrt()), "desviación {}", variance.sqrt());
+        assert!((0.0..0.2).contains(&min) && (0.8..=1.0).contains(&max), "rango [{min}, {max}]");
+    }
+
+    #[test]
+    fn perlin_is_zero_at_lattice_points() {
+        // En las esquinas de la red el vector a la esquina es nulo: ruido crudo 0
+        let generator = NoiseGenerator::new(8);
+        for i in -5..5 {
+            let c = i as f32;
+            assert!((generator.perlin(c, c * 2.0, -c) - 0.5).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn perlin_is_isotropic() {
+        // La variación media al desplazarse la misma distancia no depende de la dirección
+        // (con gradientes mal elegidos el ruido cambia mucho más en unos ejes que en otros)
+        let generator = NoiseGenerator::new(5);
+        let points = samples(&generator, 20_000);
+        let directions = [
+            Vec3::X,
+            Vec3::Y,
+            Vec3::Z,
+            Vec3::new(1.0, 1.0, 1.0).normalize(),
+            Vec3::new(1.0, -1.0, 0.0).normalize(),
+        ];
+        let variations: Vec<f32> = directions
+            .iter()
+            .map(|&direction| {
+                points
+                    .iter()
+                    .map(|&(p, v)| {
+                        let q = p + direction * 0.3;
+                        (generator.perlin(q.x, q.y, q.z) - v).powi(2)
+                    })
+                    .sum::<f32>()
+                    / points.len() as f32
+            })
+            .collect();
+        let average = variations.iter().sum::<f32>() / variations.len() as f32;
+        for variation in variations {
+            assert!((variation / average - 1.0).abs() < 0.12, "{variation} vs {average}");
+        }
+    }
+
+    #[test]
+    fn perlin_derivative_matches_finite_differences() {
+        let generator = NoiseGenerator::new(13);
+        let h = 1e-3;
+        for (p, value) in samples(&generator, 300) {
+            let (v, gradient) = generator.perlin_derivative(p.x, p.y, p.z);
+            assert!((v - value).abs() < 1e-5);
+            let numeric = Vec3::new(
+                generator.perlin(p.x + h, p.y, p.z) - generator.perlin(p.x - h, p.y, p.z),
+                generator.perlin(p.x, p.y + h, p.z) - generator.perlin(p.x, p.y - h, p.z),
+                generator.perlin(p.x, p.y, p.z + h) - generator.perlin(p.x, p.y, p.z - h),
+            ) / (2.0 * h);
+            assert!((gradient - numeric).length() < 0.02, "{gradient:?} vs {numeric:?}");
+        }
+    }
+
+    #[test]
+    fn free_functions_use_the_default_seed() {
+        let generator = NoiseGenerator::default();
+        assert_eq!(perlin_noise(1.3, 2.7, -0.4), generator.perlin(1.3, 2.7, -0.4));
+        assert_eq!(turbulence(1.3, 2.7, -0.4, 4), generator.turbulence(1.3, 2.7, -0.4, 4));
+    }
 }
@@ -0,0 +1,246 @@
+//! CELLULAR / WORLEY NOISE
+//! Cada celda de una rejilla cúbica tiene un punto característico en una posición
+//! pseudoaleatoria; el ruido en un punto se describe por las distancias a los puntos
+//! más cercanos:
+//! - F1: distancia al más cercano (manchas redondas que crecen desde cada punto)
+//! - F2: distancia al segundo más cercano
+//! - F2 - F1: vale 0 sobre los bordes entre celdas, con grosor uniforme (carriles)
+//! - El identificador de la celda ganadora permite dar un valor propio a cada celda
+
+use crate::math::Vec3;
+use super::periodic::wrap;
+use super::NoiseGenerator;
+
+/// Forma de medir la distancia a los puntos característicos
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DistanceMetric {
+    Euclidean, // Celdas redondeadas (Voronoi clásico)
+    Manhattan, // |dx| + |dy| + |dz|: celdas en forma de diamante
+    Chebyshev, // max(|dx|, |dy|, |dz|): celdas cuadradas
+}
+
+impl DistanceMetric {
+    pub fn distance(self, offset: Vec3) -> f32 {
+        match self {
+            DistanceMetric::Euclidean => offset.length(),
+            DistanceMetric::Manhattan => offset.x.abs() + offset.y.abs() + offset.z.abs(),
+            DistanceMetric::Chebyshev => offset.x.abs().max(offset.y.abs()).max(offset.z.abs()),
+        }
+    }
+}
+
+/// Resultado de evaluar el ruido celular en un punto
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CellularSample {
+    pub f1: f32,                     // Distancia al punto más cercano
+    pub f2: f32,                     // Distancia al segundo punto más cercano
+    pub feature_point: Option<Vec3>, // Punto más cercano, en el espacio de la entrada (None si es horneado)
+    pub second_point: Option<Vec3>,  // Segundo punto más cercano (None si es horneado)
+    pub cell_id: u32,                // Identificador estable de la celda del punto más cercano
+}
+
+impl CellularSample {
+    /// F2 - F1: 0 en los bordes entre celdas
+    pub fn edge(&self) -> f32 {
+        self.f2 - self.f1
+    }
+
+    /// Valor pseudoaleatorio propio de la celda, en [0, 1)
+    pub fn cell_value(&self) -> f32 {
+        self.cell_id as f32 / 4_294_967_296.0
+    }
+
+    /// Gradientes analíticos de F1 y F2 respecto a `position` (la entrada de la muestra)
+    /// con la métrica euclidiana: vectores unitarios que se alejan de cada punto
+    /// None si la muestra no guarda sus puntos característicos
+    pub fn gradients(&self, position: Vec3) -> Option<(Vec3, Vec3)> {
+        let away = |point: Vec3, distance: f32| if distance > 0.0 { (position - point) / distance } else { Vec3::ZERO };
+        Some((away(self.feature_point?, self.f1), away(self.second_point?, self.f2)))
+    }
+}
+
+impl NoiseGenerator {
+    /// Identificador de 32 bits de una celda entera: las tres coordenadas y la semilla se
+    /// mezclan en un entero (multiplicaciones y desplazamientos) para que celdas vecinas
+    /// casi nunca compartan identificador ni posición del punto
+    pub(super) fn cell_id(&self, x: i32, y: i32, z: i32) -> u32 {
+        let seed = (self.seed ^ (self.seed >> 32)) as u32;
+        let mut h = (x as u32).wrapping_mul(0x8da6_b343)
+            ^ (y as u32).wrapping_mul(0xd816_3841)
+            ^ (z as u32).wrapping_mul(0xcb1a_b31f)
+            ^ seed.wrapping_mul(0x9e37_79b9);
+        h ^= h >> 16;
+        h = h.wrapping_mul(0x7feb_352d);
+        h ^= h >> 15;
+        h = h.wrapping_mul(0x846c_a68b);
+        h ^ (h >> 16)
+    }
+
+    /// Ruido celular completo: F1, F2, punto característico e identificador de celda
+    /// `jitter` en [0, 1] mueve los puntos desde el centro de su celda (0 = rejilla regular)
+    pub fn cellular_sample(&self, position: Vec3, metric: DistanceMetric, jitter: f32) -> CellularSample {
+        self.cellular_search(position, metric, jitter, 0)
+    }
+
+    /// Como `cellular_sample`, pero las celdas se repiten cada `period` celdas por eje:
+    /// el patrón se embaldosa sin costuras (0 = sin periodo)
+    pub fn cellular_sample_periodic(&self, position: Vec3, metric: DistanceMetric, jitter: f32, period: u32) -> CellularSample {
+        self.cellular_search(position, metric, jitter, period)
+    }
+
+    fn cellular_search(&self, position: Vec3, metric: DistanceMetric, jitter: f32, period: u32) -> CellularSample {
+        let jitter = jitter.clamp(0.0, 1.0);
+        let cell = Vec3::new(position.x.floor(), position.y.floor(), position.z.floor());
+
+        let mut nearest = CellularSample {
+            f1: f32::MAX,
+            f2: f32::MAX,
+            feature_point: None,
+            second_point: None,
+            cell_id: 0,
+        };
+
+        // Sin periodo las celdas no se repiten: el identificador usa la coordenada completa
+        let index = |coordinate: f32| if period == 0 { coordinate as i32 } else { wrap(coordinate as i32, period) };
+
+        // Se buscan solo las 27 celdas vecinas: es una aproximación. Con jitter alto F2,
+        // y rara vez F1, puede estar en una celda más lejana y el resultado salta un poco
+        // en esos puntos (a cambio de no recorrer 125 celdas)
+        for i in -1..=1 {
+            for j in -1..=1 {
+                for k in -1..=1 {
+                    let neighbor = cell + Vec3::new(i as f32, j as f32, k as f32);
+                    let id = self.cell_id(index(neighbor.x), index(neighbor.y), index(neighbor.z));
+
+                    // Desplazamiento del punto desde el centro de la celda: un byte por eje
+                    let [a, b, c, _] = id.to_le_bytes();
+                    let offset = Vec3::new(a as f32, b as f32, c as f32) / 255.0 - Vec3::splat(0.5);
+                    let feature = neighbor + Vec3::splat(0.5) + offset * jitter;
+
+                    let distance = metric.distance(position - feature);
+                    if distance < nearest.f1 {
+                        nearest.f2 = nearest.f1;
+                        nearest.second_point = nearest.feature_point;
+                        nearest.f1 = distance;
+                        nearest.feature_point = Some(feature);
+                        nearest.cell_id = id;
+                    } else if distance < nearest.f2 {
+                        nearest.f2 = distance;
+                        nearest.second_point = Some(feature);
+                    }
+                }
+            }
+        }
+
+        nearest
+    }
+
+    /// Cellular/Worley Noise - distancia F1 euclidiana con los puntos en cualquier lugar
+    /// de su celda; `scale` es la cantidad de celdas por unidad
+    #[allow(dead_code)]
+    pub fn cellular(&self, position: Vec3, scale: f32) -> f32 {
+        self.cellular_sample(position * scale, DistanceMetric::Euclidean, 1.0).f1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::test_points::points;
+
+    #[test]
+    fn f1_is_below_f2_and_matches_the_feature_point() {
+        let generator = NoiseGenerator::new(12);
+        for p in points(500) {
+            for metric in [DistanceMetric::Euclidean, DistanceMetric::Manhattan, DistanceMetric::Chebyshev] {
+                let sample = generator.cellular_sample(p, metric, 1.0);
+                assert!(sample.f1 <= sample.f2);
+                assert!(sample.edge() >= 0.0);
+                assert!((metric.distance(p - sample.feature_point.unwrap()) - sample.f1).abs() < 1e-5);
+                assert!((0.0..1.0).contains(&sample.cell_value()));
+            }
+        }
+    }
+
+    #[test]
+    fn gradients_match_finite_differences() {
+        let generator = NoiseGenerator::new(9);
+        let h = 1e-3;
+        for p in points(500) {
+            let sample = generator.cellular_sample(p, DistanceMetric::Euclidean, 1.0);
+            let (d_f1, d_f2) = sample.gradients(p).unwrap();
+            assert!(((p - sample.second_point.unwrap()).length() - sample.f2).abs() < 1e-5);
+            for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+                let ahead = generator.cellular_sample(p + axis * h, DistanceMetric::Euclidean, 1.0);
+                let behind = generator.cellular_sample(p - axis * h, DistanceMetric::Euclidean, 1.0);
+                // Cerca de un borde la celda ganadora cambia: esas muestras no se comparan
+                if ahead.cell_id != behind.cell_id || ahead.second_point != behind.second_point {
+                    continue;
+                }
+                assert!((d_f1.dot(axis) - (ahead.f1 - behind.f1) / (2.0 * h)).abs() < 1e-2);
+                assert!((d_f2.dot(axis) - (ahead.f2 - behind.f2) / (2.0 * h)).abs() < 1e-2);
+            }
+        }
+    }
+
+    #[test]
+    fn metrics_are_ordered() {
+        // Para cualquier vector: Chebyshev <= Euclidiana <= Manhattan
+        let generator = NoiseGenerator::new(4);
+        for p in points(500) {
+            let chebyshev = generator.cellular_sample(p, DistanceMetric::Chebyshev, 1.0).f1;
+            let euclidean = generator.cellular_sample(p, DistanceMetric::Euclidean, 1.0).f1;
+            let manhattan = generator.cellular_sample(p, DistanceMetric::Manhattan, 1.0).f1;
+            assert!(chebyshev <= euclidean + 1e-6 && euclidean <= manhattan + 1e-6);
+        }
+    }
+
+    #[test]
+    fn zero_jitter_gives_a_regular_grid() {
+        let generator = NoiseGenerator::new(1);
+        let sample = generator.cellular_sample(Vec3::new(3.5, -1.5, 7.5), DistanceMetric::Euclidean, 0.0);
+        assert!(sample.f1 < 1e-6);
+        assert!((sample.f2 - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn periodic_cells_tile() {
+        let generator = NoiseGenerator::new(8);
+        for p in points(500) {
+            let sample = generator.cellular_sample_periodic(p, DistanceMetric::Euclidean, 1.0, 5);
+            let shifted = generator.cellular_sample_periodic(p + Vec3::new(5.0, -10.0, 15.0), DistanceMetric::Euclidean, 1.0, 5);
+            assert!((sample.f1 - shifted.f1).abs() < 1e-3 && (sample.f2 - shifted.f2).abs() < 1e-3);
+            assert_eq!(sample.cell_id, shifted.cell_id);
+        }
+    }
+
+    #[test]
+    fn cell_ids_are_stable_inside_a_cell() {
+        // Dos puntos muy cercanos al mismo punto característico comparten identificador
+        let generator = NoiseGenerator::new(6);
+        for p in points(500) {
+            let sample = generator.cellular_sample(p, DistanceMetric::Euclidean, 0.8);
+            let near = sample.feature_point.unwrap() + Vec3::new(0.01, -0.01, 0.005);
+            let other = generator.cellular_sample(near, DistanceMetric::Euclidean, 0.8);
+            assert_eq!(sample.cell_id, other.cell_id);
+        }
+    }
+
+    #[test]
+    fn neighboring_cells_have_distinct_ids() {
+        let generator = NoiseGenerator::new(3);
+        let mut repeated = 0;
+        for x in -20..20 {
+            for y in -20..20 {
+                for z in -5..5 {
+                    let id = generator.cell_id(x, y, z);
+                    if id == generator.cell_id(x + 1, y, z) || id == generator.cell_id(x, y + 1, z) || id == generator.cell_id(x, y, z + 1) {
+                        repeated += 1;
+                    }
+                }
+            }
+        }
+        assert_eq!(repeated, 0);
+    }
+}
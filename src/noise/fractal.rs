@@ -0,0 +1,291 @@
+//! RUIDO FRACTAL
+//! Suma de octavas de un ruido base: cada octava tiene `lacunarity` veces la frecuencia
+//! y `gain` veces la amplitud de la anterior. Las variantes cambian qué se suma:
+//! - fBm: el ruido tal cual (relieve suave)
+//! - Turbulencia: su valor absoluto (pliegues marcados, aspecto de fuego)
+//! - Billow: el valor absoluto centrado en 0 (nubes hinchadas)
+//! - Ridged multifractal: crestas finas donde el ruido cruza 0; cada octava se pesa con
+//!   la anterior, así el detalle se concentra sobre las crestas (filamentos de plasma)
+//! - Domain warping: se deforma la posición con el mismo ruido antes de evaluarlo
+//!
+//! Para que las rejillas de las octavas no se alineen (y se noten los ejes), cada octava
+//! se gira respecto a la anterior
+
+use crate::math::{Quat, Vec3};
+use super::NoiseGenerator;
+
+/// Ruido base de las octavas
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Basis {
+    Perlin,
+    Simplex,
+}
+
+/// Configuración de un ruido fractal
+/// Se arma encadenando métodos: `Fractal::new().octaves(5).gain(0.6).ridged(&ruido, p)`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fractal {
+    pub basis: Basis,
+    pub octaves: u32,
+    pub lacunarity: f32, // Multiplicador de frecuencia entre octavas
+    pub gain: f32,       // Multiplicador de amplitud entre octavas
+    pub rotation: Quat,  // Giro aplicado a la posición antes de cada octava
+}
+
+impl Default for Fractal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Fractal {
+    /// Giro entre octavas: un ángulo irracional alrededor de un eje no alineado
+    fn octave_rotation() -> Quat {
+        Quat::from_axis_angle(Vec3::new(1.0, 2.0, 3.0), 0.7)
+    }
+
+    /// 4 octavas de Perlin, lacunaridad 2, ganancia 0.5 y octavas giradas
+    pub fn new() -> Self {
+        Self {
+            basis: Basis::Perlin,
+            octaves: 4,
+            lacunarity: 2.0,
+            gain: 0.5,
+            rotation: Self::octave_rotation(),
+        }
+    }
+
+    pub fn basis(mut self, basis: Basis) -> Self {
+        self.basis = basis;
+        self
+    }
+
+    pub fn octaves(mut self, octaves: u32) -> Self {
+        self.octaves = octaves;
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn lacunarity(mut self, lacunarity: f32) -> Self {
+        self.lacunarity = lacunarity;
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn gain(mut self, gain: f32) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    /// Activa o desactiva el giro entre octavas
+    #[allow(dead_code)]
+    pub fn rotate_octaves(mut self, enabled: bool) -> Self {
+        self.rotation = if enabled { Self::octave_rotation() } else { Quat::IDENTITY };
+        self
+    }
+
+    /// Ruido base con signo, en [-1, 1]
+    fn signed(&self, noise: &NoiseGenerator, p: Vec3) -> f32 {
+        let value = match self.basis {
+            Basis::Perlin => noise.perlin(p.x, p.y, p.z),
+            Basis::Simplex => noise.simplex3(p.x, p.y, p.z),
+        };
+        value * 2.0 - 1.0
+    }
+
+    /// Ruido base en [0, 1] con su gradiente analítico
+    fn derivative(&self, noise: &NoiseGenerator, p: Vec3) -> (f32, Vec3) {
+        match self.basis {
+            Basis::Perlin => noise.perlin_derivative(p.x, p.y, p.z),
+            Basis::Simplex => noise.simplex3_derivative(p.x, p.y, p.z),
+        }
+    }
+
+    /// Recorre las octavas: `octave(señal, peso_anterior)` devuelve (aporte, peso_siguiente)
+    /// Retorna la suma de los aportes ponderados y la suma de las amplitudes
+    fn accumulate<F: FnMut(f32, f32) -> (f32, f32)>(&self, noise: &NoiseGenerator, p: Vec3, mut octave: F) -> (f32, f32) {
+        let mut position = p;
+        let mut amplitude = 1.0;
+        let mut total = 0.0;
+        let mut amplitude_sum = 0.0;
+        let mut weight = 1.0;
+
+        for _ in 0..self.octaves.max(1) {
+            let (value, next_weight) = octave(self.signed(noise, position), weight);
+            total += value * amplitude;
+            amplitude_sum += amplitude;
+            weight = next_weight;
+
+            position = self.rotation.rotate(position) * self.lacunarity;
+            amplitude *= self.gain;
+        }
+
+        (total, amplitude_sum)
+    }
+
+    /// fBm (movimiento browniano fraccionario) en [0, 1], media 0.5
+    #[allow(dead_code)]
+    pub fn fbm(&self, noise: &NoiseGenerator, p: Vec3) -> f32 {
+        let (total, amplitude_sum) = self.accumulate(noise, p, |n, _| (n, 1.0));
+        (total / amplitude_sum * 0.5 + 0.5).clamp(0.0, 1.0)
+    }
+
+    /// fBm con su gradiente analítico: (valor igual a `fbm`, ∂valor/∂p)
+    /// La octava k se evalúa en Lᵏ·Rᵏ·p, así que su gradiente vuelve al espacio de `p`
+    /// deshaciendo el giro acumulado y multiplicando por la frecuencia Lᵏ
+    #[allow(dead_code)]
+    pub fn fbm_derivative(&self, noise: &NoiseGenerator, p: Vec3) -> (f32, Vec3) {
+        let mut position = p;
+        let mut frame = Quat::IDENTITY; // Giro acumulado Rᵏ
+        let mut frequency = 1.0;
+        let mut amplitude = 1.0;
+        let mut total = 0.0;
+        let mut gradient = Vec3::ZERO;
+        let mut amplitude_sum = 0.0;
+
+        for _ in 0..self.octaves.max(1) {
+            let (value, octave_gradient) = self.derivative(noise, position);
+            total += (value * 2.0 - 1.0) * amplitude;
+            gradient += frame.conjugate().rotate(octave_gradient) * (amplitude * frequency);
+            amplitude_sum += amplitude;
+
+            position = self.rotation.rotate(position) * self.lacunarity;
+            frame = self.rotation * frame;
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+
+        // Misma normalización que `fbm`: el factor 2 de la señal se cancela con el 0.5
+        ((total / amplitude_sum * 0.5 + 0.5).clamp(0.0, 1.0), gradient / amplitude_sum)
+    }
+
+    /// Turbulencia: suma de |ruido| en [0, 1]; los cruces por 0 quedan como pliegues oscuros
+    #[allow(dead_code)]
+    pub fn turbulence(&self, noise: &NoiseGenerator, p: Vec3) -> f32 {
+        let (total, amplitude_sum) = self.accumulate(noise, p, |n, _| (n.abs(), 1.0));
+        (total / amplitude_sum).clamp(0.0, 1.0)
+    }
+
+    /// Billow: la turbulencia llevada a [-1, 1], centrada como el fBm con signo
+    #[allow(dead_code)]
+    pub fn billow(&self, noise: &NoiseGenerator, p: Vec3) -> f32 {
+        let (total, amplitude_sum) = self.accumulate(noise, p, |n, _| (n.abs() * 2.0 - 1.0, 1.0));
+        (total / amplitude_sum).clamp(-1.0, 1.0)
+    }
+
+    /// Ridged multifractal (Musgrave) en [0, 1]: 1 sobre las crestas
+    /// Cada octava se multiplica por la señal de la anterior, así el detalle fino solo
+    /// aparece a lo largo de las crestas y los valles quedan lisos
+    pub fn ridged(&self, noise: &NoiseGenerator, p: Vec3) -> f32 {
+        let (total, amplitude_sum) = self.accumulate(noise, p, |n, weight| {
+            let ridge = 1.0 - n.abs();
+            let signal = ridge * ridge * weight;
+            (signal, (signal * 2.0).clamp(0.0, 1.0))
+        });
+        (total / amplitude_sum).clamp(0.0, 1.0)
+    }
+
+    /// Deforma `p` con el propio fBm `iterations` veces (cada vez sobre la posición ya
+    /// deformada); `strength` es el desplazamiento máximo por iteración
+    pub fn warp(&self, noise: &NoiseGenerator, p: Vec3, strength: f32, iterations: u32) -> Vec3 {
+        let mut q = p;
+        for _ in 0..iterations {
+            // Tres muestras desplazadas: una componente independiente por eje
+            let offset = Vec3::new(
+                self.fbm(noise, q),
+                self.fbm(noise, q + Vec3::new(5.2, 1.3, 2.8)),
+                self.fbm(noise, q + Vec3::new(1.7, 9.2, 4.1)),
+            ) * 2.0
+                - Vec3::ONE;
+            q = p + offset * strength;
+        }
+        q
+    }
+
+    /// fBm sobre la posición deformada, en [0, 1]
+    #[allow(dead_code)]
+    pub fn domain_warp(&self, noise: &NoiseGenerator, p: Vec3, strength: f32, iterations: u32) -> f32 {
+        self.fbm(noise, self.warp(noise, p, strength, iterations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::test_points::points;
+
+    #[test]
+    fn variants_stay_in_range() {
+        let noise = NoiseGenerator::new(17);
+        for basis in [Basis::Perlin, Basis::Simplex] {
+            let fractal = Fractal::new().basis(basis).octaves(5);
+            for p in points(400) {
+                assert!((0.0..=1.0).contains(&fractal.fbm(&noise, p)));
+                assert!((0.0..=1.0).contains(&fractal.turbulence(&noise, p)));
+                assert!((-1.0..=1.0).contains(&fractal.billow(&noise, p)));
+                assert!((0.0..=1.0).contains(&fractal.ridged(&noise, p)));
+                assert!((0.0..=1.0).contains(&fractal.domain_warp(&noise, p, 0.5, 2)));
+            }
+        }
+    }
+
+    #[test]
+    fn single_octave_is_the_basis() {
+        let noise = NoiseGenerator::new(2);
+        let fractal = Fractal::new().octaves(1);
+        for p in points(400) {
+            assert!((fractal.fbm(&noise, p) - noise.perlin(p.x, p.y, p.z)).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn more_gain_means_more_detail() {
+        // Con más ganancia pesan más las octavas altas: el valor cambia más entre vecinos
+        let noise = NoiseGenerator::new(8);
+        let roughness = |fractal: Fractal| {
+            points(400)
+                .map(|p| (fractal.fbm(&noise, p + Vec3::X * 0.02) - fractal.fbm(&noise, p)).abs())
+                .sum::<f32>()
+        };
+        assert!(roughness(Fractal::new().octaves(6).gain(0.3)) < roughness(Fractal::new().octaves(6).gain(0.7)));
+    }
+
+    #[test]
+    fn octave_rotation_changes_the_pattern() {
+        let noise = NoiseGenerator::new(5);
+        let rotated = Fractal::new();
+        let aligned = Fractal::new().rotate_octaves(false);
+        assert!(points(400).any(|p| (rotated.fbm(&noise, p) - aligned.fbm(&noise, p)).abs() > 0.01));
+    }
+
+    #[test]
+    fn fbm_derivative_matches_finite_differences() {
+        let noise = NoiseGenerator::new(11);
+        let h = 1e-3;
+        for basis in [Basis::Perlin, Basis::Simplex] {
+            let fractal = Fractal::new().basis(basis).octaves(4);
+            for p in points(400).take(150) {
+                let (value, gradient) = fractal.fbm_derivative(&noise, p);
+                assert!((value - fractal.fbm(&noise, p)).abs() < 1e-5);
+                let numeric = Vec3::new(
+                    fractal.fbm(&noise, p + Vec3::X * h) - fractal.fbm(&noise, p - Vec3::X * h),
+                    fractal.fbm(&noise, p + Vec3::Y * h) - fractal.fbm(&noise, p - Vec3::Y * h),
+                    fractal.fbm(&noise, p + Vec3::Z * h) - fractal.fbm(&noise, p - Vec3::Z * h),
+                ) / (2.0 * h);
+                assert!((gradient - numeric).length() < 0.1, "{gradient:?} vs {numeric:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn warp_is_bounded_by_strength() {
+        let noise = NoiseGenerator::new(3);
+        let fractal = Fractal::new().octaves(3);
+        for p in points(400) {
+            let q = fractal.warp(&noise, p, 0.4, 3);
+            let d = q - p;
+            assert!(d.x.abs() <= 0.4 + 1e-5 && d.y.abs() <= 0.4 + 1e-5 && d.z.abs() <= 0.4 + 1e-5);
+            assert_eq!(fractal.warp(&noise, p, 0.0, 3), p);
+        }
+    }
+}
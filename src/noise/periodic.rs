@@ -0,0 +1,213 @@
+//! RUIDO PERIÓDICO Y BUCLES DE TIEMPO
+//! Para exportar animaciones que se repiten sin salto (GIFs en bucle), todo lo que
+//! depende del tiempo tiene que volver a su valor inicial al final del bucle:
+//! - Perlin periódico: las esquinas de la rejilla se envuelven cada `period` celdas por
+//!   eje, así el ruido se repite exactamente en ese eje (texturas que se embaldosan)
+//! - Simplex periódico: cada eje se recorre sobre un círculo en 4D (un toro), con
+//!   cualquier periodo real
+//! - Bucle de tiempo: en lugar de avanzar en línea recta por una dimensión del ruido,
+//!   el tiempo recorre un círculo en dos dimensiones y vuelve al punto de partida
+//! - Rebanadas de tiempo: un campo 3D periódico evoluciona sin deslizarse mezclando
+//!   copias desplazadas de sí mismo, así un volumen horneado da lo mismo que el exacto
+
+use std::f32::consts::TAU;
+use crate::math::{smoothstep, Vec3};
+use super::{fade, grad, lerp, NoiseGenerator};
+
+/// Envuelve un índice de la rejilla; periodo 0 = sin periodo (la tabla se repite cada 256)
+pub(super) fn wrap(index: i32, period: u32) -> i32 {
+    if period == 0 {
+        index & 255
+    } else {
+        index.rem_euclid(period as i32)
+    }
+}
+
+/// Las dos rebanadas de tiempo que se mezclan en `w`: (desplazamiento, peso)
+/// Cada valor entero de `w` es una rebanada, el campo desplazado a otro lugar de su
+/// periodo `period`; entre dos rebanadas consecutivas los pesos siguen un smoothstep
+/// (suman 1). Como `w` no tiene límite, los desplazamientos se envuelven al periodo
+pub fn time_slices(w: f32, period: f32) -> [(Vec3, f32); 2] {
+    let slice = w.floor();
+    let blend = smoothstep(0.0, 1.0, w - slice);
+    let offset = |k: f32| Vec3::new((k * 3.7).rem_euclid(period), (k * 1.9).rem_euclid(period), (k * 5.3).rem_euclid(period));
+    [(offset(slice), 1.0 - blend), (offset(slice + 1.0), blend)]
+}
+
+impl NoiseGenerator {
+    /// Perlin 3D que se repite cada `period[eje]` unidades en cada eje (0 = sin periodo)
+    /// Con los tres periodos en 0 es exactamente `perlin`
+    pub fn perlin_periodic(&self, x: f32, y: f32, z: f32, period: [u32; 3]) -> f32 {
+        let (x0, y0, z0) = (x.floor() as i32, y.floor() as i32, z.floor() as i32);
+        let (xa, xb) = (wrap(x0, period[0]), wrap(x0 + 1, period[0]));
+        let (ya, yb) = (wrap(y0, period[1]), wrap(y0 + 1, period[1]));
+        let (za, zb) = (wrap(z0, period[2]), wrap(z0 + 1, period[2]));
+
+        let xf = x - x.floor();
+        let yf = y - y.floor();
+        let zf = z - z.floor();
+        let (u, v, w) = (fade(xf), fade(yf), fade(zf));
+
+        // Mismas 8 esquinas que `perlin`, con los índices ya envueltos
+        let x1 = lerp(grad(self.hash_index(xa, ya, za), xf, yf, zf), grad(self.hash_index(xb, ya, za), xf - 1.0, yf, zf), u);
+        let x2 = lerp(
+            grad(self.hash_index(xa, yb, za), xf, yf - 1.0, zf),
+            grad(self.hash_index(xb, yb, za), xf - 1.0, yf - 1.0, zf),
+            u,
+        );
+        let x3 = lerp(
+            grad(self.hash_index(xa, ya, zb), xf, yf, zf - 1.0),
+            grad(self.hash_index(xb, ya, zb), xf - 1.0, yf, zf - 1.0),
+            u,
+        );
+        let x4 = lerp(
+            grad(self.hash_index(xa, yb, zb), xf, yf - 1.0, zf - 1.0),
+            grad(self.hash_index(xb, yb, zb), xf - 1.0, yf - 1.0, zf - 1.0),
+            u,
+        );
+        let value = lerp(lerp(x1, x2, v), lerp(x3, x4, v), w);
+
+        ((value + 1.0) * 0.5).clamp(0.0, 1.0)
+    }
+
+    /// Turbulencia periódica: como `turbulence`, pero cada octava dobla también el periodo
+    /// para que toda la suma se repita cada `period` unidades
+    pub fn turbulence_periodic(&self, x: f32, y: f32, z: f32, octaves: i32, period: [u32; 3]) -> f32 {
+        let mut value = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        let mut period = period;
+
+        for _ in 0..octaves {
+            value += amplitude * self.perlin_periodic(x * frequency, y * frequency, z * frequency, period);
+            amplitude *= 0.5;
+            frequency *= 2.0;
+            period = period.map(|p| p * 2);
+        }
+
+        value
+    }
+
+    /// Turbulencia periódica de periodo `period` en los tres ejes que evoluciona con `w`
+    /// sin deslizarse (ver `time_slices`): la superficie hierve en su sitio como con
+    /// `turbulence4`, pero solo lee un campo 3D que se puede hornear
+    pub fn turbulence_slices(&self, p: Vec3, w: f32, octaves: i32, period: u32) -> f32 {
+        time_slices(w, period as f32)
+            .iter()
+            .map(|&(offset, weight)| {
+                let q = p + offset;
+                weight * self.turbulence_periodic(q.x, q.y, q.z, octaves, [period; 3])
+            })
+            .sum()
+    }
+
+    /// Simplex 2D que se repite cada `period.0` en x y `period.1` en y
+    /// Cada eje se recorre sobre un círculo de perímetro igual a su periodo, así la
+    /// escala del ruido es la misma que la de `simplex2` (salida en [0, 1])
+    #[allow(dead_code)]
+    pub fn simplex2_periodic(&self, x: f32, y: f32, period: (f32, f32)) -> f32 {
+        let (sin_x, cos_x) = (TAU * x / period.0).sin_cos();
+        let (sin_y, cos_y) = (TAU * y / period.1).sin_cos();
+        let (radius_x, radius_y) = (period.0 / TAU, period.1 / TAU);
+        self.simplex4(radius_x * cos_x, radius_x * sin_x, radius_y * cos_y, radius_y * sin_y)
+    }
+}
+
+/// Bucle de animación de `duration` segundos
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeLoop {
+    pub duration: f32, // Segundos hasta que la animación vuelve al principio
+}
+
+impl TimeLoop {
+    pub fn new(duration: f32) -> Self {
+        Self { duration: duration.max(1e-3) }
+    }
+
+    /// Tiempo dentro del bucle, en [0, duration)
+    pub fn wrap(&self, time: f32) -> f32 {
+        time.rem_euclid(self.duration)
+    }
+
+    /// Frecuencia angular más cercana a `omega` que da vueltas enteras en un bucle
+    /// (al menos una vuelta en el mismo sentido: un bucle corto no detiene el giro)
+    pub fn angular_frequency(&self, omega: f32) -> f32 {
+        if omega == 0.0 {
+            return 0.0;
+        }
+        let turns = (omega.abs() * self.duration / TAU).round().max(1.0);
+        omega.signum() * turns * TAU / self.duration
+    }
+
+    /// Ciclos enteros (al menos uno) de un proceso que se repite `rate` veces por segundo
+    pub fn cycles(&self, rate: f32) -> u32 {
+        (rate * self.duration).round().max(1.0) as u32
+    }
+
+    /// Avance del ruido en el bucle: en lugar de (0, time · speed) recorre un círculo en
+    /// dos dimensiones del ruido con la misma rapidez `speed`, y vuelve a (0, 0) al
+    /// terminar el bucle (para ruido 4D: la coordenada z y el tiempo w)
+    pub fn circle(&self, time: f32, speed: f32) -> (f32, f32) {
+        let radius = speed * self.duration / TAU;
+        let (sin, cos) = (TAU * time / self.duration).sin_cos();
+        (radius * (cos - 1.0), radius * sin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Vec3;
+    use crate::noise::test_points::points;
+
+    #[test]
+    fn perlin_repeats_with_its_period() {
+        let noise = NoiseGenerator::new(7);
+        for Vec3 { x, y, z } in points(300) {
+            let value = noise.perlin_periodic(x, y, z, [4, 3, 5]);
+            assert!((value - noise.perlin_periodic(x + 4.0, y, z, [4, 3, 5])).abs() < 1e-4);
+            assert!((value - noise.perlin_periodic(x, y - 3.0, z, [4, 3, 5])).abs() < 1e-4);
+            assert!((value - noise.perlin_periodic(x, y, z + 10.0, [4, 3, 5])).abs() < 1e-4);
+            // Sin periodo es el Perlin de siempre
+            assert_eq!(noise.perlin_periodic(x, y, z, [0, 0, 0]), noise.perlin(x, y, z));
+        }
+    }
+
+    #[test]
+    fn turbulence_and_simplex_repeat() {
+        let noise = NoiseGenerator::new(3);
+        for Vec3 { x, y, z } in points(300) {
+            let value = noise.turbulence_periodic(x, y, z, 4, [0, 0, 2]);
+            assert!((value - noise.turbulence_periodic(x, y, z + 2.0, 4, [0, 0, 2])).abs() < 1e-4);
+
+            let value = noise.simplex2_periodic(x, y, (3.5, 6.0));
+            assert!((value - noise.simplex2_periodic(x + 3.5, y - 6.0, (3.5, 6.0))).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn time_loop_returns_to_the_start() {
+        let time_loop = TimeLoop::new(8.0);
+        assert_eq!(time_loop.circle(0.0, 0.3), (0.0, 0.0));
+        let (a, b) = time_loop.circle(8.0, 0.3);
+        assert!(a.abs() < 1e-5 && b.abs() < 1e-5);
+        // Al principio avanza como el tiempo lineal
+        let (_, w) = time_loop.circle(0.01, 0.3);
+        assert!((w - 0.003).abs() < 1e-5);
+
+        let omega = time_loop.angular_frequency(2.0);
+        let turns = omega * 8.0 / TAU;
+        assert!((turns - turns.round()).abs() < 1e-4 && (omega - 2.0).abs() < TAU / 16.0);
+        assert_eq!(time_loop.cycles(0.15), 1);
+        assert!((time_loop.wrap(19.0) - 3.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn short_loops_keep_one_turn() {
+        // A -0.3 rad/s un bucle de 4 s daría 0.19 vueltas: se redondea a una, hacia atrás
+        let time_loop = TimeLoop::new(4.0);
+        assert!((time_loop.angular_frequency(-0.3) + TAU / 4.0).abs() < 1e-5);
+        assert!((time_loop.angular_frequency(0.3) - TAU / 4.0).abs() < 1e-5);
+        assert_eq!(time_loop.angular_frequency(0.0), 0.0);
+    }
+}
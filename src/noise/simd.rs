@@ -0,0 +1,919 @@
+//! RUIDO EN LOTES (SIMD)
+//! `render_spans` sombrea los fragmentos de 4 en 4, y el shader de la estrella evalúa
+//! su ruido para los 4 a la vez. Estas variantes evalúan 4 u 8 puntos de una vez con
+//! una estructura de arreglos (`Vec3Batch`: todas las x juntas, todas las y, todas las
+//! z), así cada operación aritmética trabaja sobre todos los carriles en una instrucción:
+//! - `F32x4`: un registro SSE2 en x86_64 (`std::arch`, siempre disponible en esa
+//!   arquitectura) y un arreglo de 4 `f32` en las demás
+//! - `F32x8`: dos `F32x4`. Con SSE2 no rinde más que dos lotes de 4 (hay el doble de
+//!   valores vivos para los mismos 16 registros), por eso el shader usa `F32x4`
+//!
+//! Solo los saltos en la tabla de permutación quedan carril por carril (no hay
+//! instrucciones de "gather" en SSE2). Las ramas de las versiones escalares se cambian
+//! por máscaras y `select`, y las operaciones siguen el mismo orden, así los resultados
+//! coinciden con `perlin`, `simplex3`, `simplex4` y `cellular_sample` salvo redondeo
+
+use std::ops::{Add, Mul, Sub};
+use crate::math::Vec3;
+use super::simplex::{F3, F4, G3, G4, GRADIENTS_4D, SCALE_3D, SCALE_4D};
+use super::periodic::{time_slices, wrap};
+use super::{CellularSample, DistanceMetric, NoiseGenerator, GRADIENTS};
+
+/// Operaciones comunes a `F32x4` y `F32x8`: las funciones de ruido en lotes son
+/// genéricas sobre el ancho
+/// Las comparaciones devuelven máscaras (todos los bits en 1 donde se cumplen) que se
+/// combinan con `and`/`or`/`not` y eligen valores con `select`
+pub trait Simd: Copy + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> {
+    /// Cantidad de carriles
+    const LANES: usize;
+    /// Arreglo de un `f32` por carril
+    type Array: Copy + Default + AsRef<[f32]> + AsMut<[f32]>;
+    /// Arreglo de un `u32` por carril (identificadores de celda)
+    type Ids: Copy + Default + AsRef<[u32]> + AsMut<[u32]>;
+
+    fn splat(value: f32) -> Self;
+    fn from_array(values: Self::Array) -> Self;
+    fn to_array(self) -> Self::Array;
+    fn min(self, other: Self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn floor(self) -> Self;
+    fn lt(self, other: Self) -> Self;
+    fn gt(self, other: Self) -> Self;
+    fn ge(self, other: Self) -> Self;
+    fn and(self, other: Self) -> Self;
+    fn or(self, other: Self) -> Self;
+    fn not(self) -> Self;
+    /// `a` donde la máscara está encendida y `b` donde no
+    fn select(mask: Self, a: Self, b: Self) -> Self;
+    /// Bit `i` encendido si la máscara del carril `i` lo está
+    fn bitmask(self) -> u32;
+
+    /// Lote con el valor que `f` da para cada carril
+    fn from_fn(mut f: impl FnMut(usize) -> f32) -> Self {
+        let mut values = Self::Array::default();
+        for (i, value) in values.as_mut().iter_mut().enumerate() {
+            *value = f(i);
+        }
+        Self::from_array(values)
+    }
+
+    fn lane(self, index: usize) -> f32 {
+        self.to_array().as_ref()[index]
+    }
+
+    /// 1.0 donde la máscara está encendida y 0.0 donde no
+    fn ones(self) -> Self {
+        self.and(Self::splat(1.0))
+    }
+
+    fn clamp(self, min: f32, max: f32) -> Self {
+        self.max(Self::splat(min)).min(Self::splat(max))
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod lanes {
+    //! 4 carriles en un registro SSE2. SSE2 es parte de la base de x86_64: las
+    //! intrínsecas siempre están disponibles y los bloques `unsafe` no tienen otra condición
+    use std::arch::x86_64::*;
+    use std::ops::{Add, Mul, Sub};
+    use super::Simd;
+
+    #[derive(Clone, Copy, Debug)]
+    pub struct F32x4(__m128);
+
+    impl Add for F32x4 {
+        type Output = F32x4;
+        fn add(self, other: F32x4) -> F32x4 {
+            Self(unsafe { _mm_add_ps(self.0, other.0) })
+        }
+    }
+
+    impl Sub for F32x4 {
+        type Output = F32x4;
+        fn sub(self, other: F32x4) -> F32x4 {
+            Self(unsafe { _mm_sub_ps(self.0, other.0) })
+        }
+    }
+
+    impl Mul for F32x4 {
+        type Output = F32x4;
+        fn mul(self, other: F32x4) -> F32x4 {
+            Self(unsafe { _mm_mul_ps(self.0, other.0) })
+        }
+    }
+
+    impl Simd for F32x4 {
+        const LANES: usize = 4;
+        type Array = [f32; 4];
+        type Ids = [u32; 4];
+
+        fn splat(value: f32) -> Self {
+            Self(unsafe { _mm_set1_ps(value) })
+        }
+
+        fn from_array(values: [f32; 4]) -> Self {
+            Self(unsafe { _mm_loadu_ps(values.as_ptr()) })
+        }
+
+        fn to_array(self) -> [f32; 4] {
+            let mut values = [0.0; 4];
+            unsafe { _mm_storeu_ps(values.as_mut_ptr(), self.0) };
+            values
+        }
+
+        fn min(self, other: Self) -> Self {
+            Self(unsafe { _mm_min_ps(self.0, other.0) })
+        }
+
+        fn max(self, other: Self) -> Self {
+            Self(unsafe { _mm_max_ps(self.0, other.0) })
+        }
+
+        fn sqrt(self) -> Self {
+            Self(unsafe { _mm_sqrt_ps(self.0) })
+        }
+
+        /// Borra el bit de signo
+        fn abs(self) -> Self {
+            Self(unsafe { _mm_andnot_ps(_mm_set1_ps(-0.0), self.0) })
+        }
+
+        /// SSE2 no tiene `floor`: se trunca hacia 0 y se resta 1 donde el truncado quedó
+        /// por encima (números negativos). Válido para |x| < 2³¹, como `x.floor() as i32`
+        fn floor(self) -> Self {
+            unsafe {
+                let truncated = _mm_cvtepi32_ps(_mm_cvttps_epi32(self.0));
+                let above = _mm_cmpgt_ps(truncated, self.0);
+                Self(_mm_sub_ps(truncated, _mm_and_ps(above, _mm_set1_ps(1.0))))
+            }
+        }
+
+        fn lt(self, other: Self) -> Self {
+            Self(unsafe { _mm_cmplt_ps(self.0, other.0) })
+        }
+
+        fn gt(self, other: Self) -> Self {
+            Self(unsafe { _mm_cmpgt_ps(self.0, other.0) })
+        }
+
+        fn ge(self, other: Self) -> Self {
+            Self(unsafe { _mm_cmpge_ps(self.0, other.0) })
+        }
+
+        fn and(self, other: Self) -> Self {
+            Self(unsafe { _mm_and_ps(self.0, other.0) })
+        }
+
+        fn or(self, other: Self) -> Self {
+            Self(unsafe { _mm_or_ps(self.0, other.0) })
+        }
+
+        fn not(self) -> Self {
+            Self(unsafe { _mm_xor_ps(self.0, _mm_castsi128_ps(_mm_set1_epi32(-1))) })
+        }
+
+        fn select(mask: Self, a: Self, b: Self) -> Self {
+            Self(unsafe { _mm_or_ps(_mm_and_ps(mask.0, a.0), _mm_andnot_ps(mask.0, b.0)) })
+        }
+
+        /// El bit de signo de cada carril
+        fn bitmask(self) -> u32 {
+            unsafe { _mm_movemask_ps(self.0) as u32 }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+mod lanes {
+    //! 4 carriles en un arreglo: el compilador puede vectorizar los bucles por su cuenta
+    //! Las máscaras usan la misma representación que SSE2 (todos los bits en 1)
+    use std::ops::{Add, Mul, Sub};
+    use super::Simd;
+
+    #[derive(Clone, Copy, Debug)]
+    pub struct F32x4([f32; 4]);
+
+    const TRUE: f32 = f32::from_bits(u32::MAX);
+
+    impl F32x4 {
+        fn map(self, f: impl Fn(f32) -> f32) -> Self {
+            Self(self.0.map(f))
+        }
+
+        fn zip(self, other: Self, f: impl Fn(f32, f32) -> f32) -> Self {
+            Self(std::array::from_fn(|i| f(self.0[i], other.0[i])))
+        }
+
+        fn bits(self, other: Self, f: impl Fn(u32, u32) -> u32) -> Self {
+            self.zip(other, |a, b| f32::from_bits(f(a.to_bits(), b.to_bits())))
+        }
+
+        fn mask(self, other: Self, f: impl Fn(f32, f32) -> bool) -> Self {
+            self.zip(other, |a, b| if f(a, b) { TRUE } else { 0.0 })
+        }
+    }
+
+    impl Add for F32x4 {
+        type Output = F32x4;
+        fn add(self, other: F32x4) -> F32x4 {
+            self.zip(other, |a, b| a + b)
+        }
+    }
+
+    impl Sub for F32x4 {
+        type Output = F32x4;
+        fn sub(self, other: F32x4) -> F32x4 {
+            self.zip(other, |a, b| a - b)
+        }
+    }
+
+    impl Mul for F32x4 {
+        type Output = F32x4;
+        fn mul(self, other: F32x4) -> F32x4 {
+            self.zip(other, |a, b| a * b)
+        }
+    }
+
+    impl Simd for F32x4 {
+        const LANES: usize = 4;
+        type Array = [f32; 4];
+        type Ids = [u32; 4];
+
+        fn splat(value: f32) -> Self {
+            Self([value; 4])
+        }
+
+        fn from_array(values: [f32; 4]) -> Self {
+            Self(values)
+        }
+
+        fn to_array(self) -> [f32; 4] {
+            self.0
+        }
+
+        // Mismo criterio que SSE2 ante NaN: si la comparación falla gana `other`
+        fn min(self, other: Self) -> Self {
+            self.zip(other, |a, b| if a < b { a } else { b })
+        }
+
+        fn max(self, other: Self) -> Self {
+            self.zip(other, |a, b| if a > b { a } else { b })
+        }
+
+        fn sqrt(self) -> Self {
+            self.map(f32::sqrt)
+        }
+
+        fn abs(self) -> Self {
+            self.map(f32::abs)
+        }
+
+        fn floor(self) -> Self {
+            self.map(f32::floor)
+        }
+
+        fn lt(self, other: Self) -> Self {
+            self.mask(other, |a, b| a < b)
+        }
+
+        fn gt(self, other: Self) -> Self {
+            self.mask(other, |a, b| a > b)
+        }
+
+        fn ge(self, other: Self) -> Self {
+            self.mask(other, |a, b| a >= b)
+        }
+
+        fn and(self, other: Self) -> Self {
+            self.bits(other, |a, b| a & b)
+        }
+
+        fn or(self, other: Self) -> Self {
+            self.bits(other, |a, b| a | b)
+        }
+
+        fn not(self) -> Self {
+            self.bits(self, |a, _| !a)
+        }
+
+        fn select(mask: Self, a: Self, b: Self) -> Self {
+            mask.and(a).or(mask.not().and(b))
+        }
+
+        fn bitmask(self) -> u32 {
+            self.0.iter().enumerate().map(|(i, v)| (v.to_bits() >> 31) << i).sum()
+        }
+    }
+}
+
+pub use lanes::F32x4;
+
+/// 8 carriles como dos `F32x4`: los carriles 0-3 en `low` y 4-7 en `high`
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug)]
+pub struct F32x8 {
+    pub low: F32x4,
+    pub high: F32x4,
+}
+
+impl F32x8 {
+    fn pair(self, other: Self, f: impl Fn(F32x4, F32x4) -> F32x4) -> Self {
+        Self { low: f(self.low, other.low), high: f(self.high, other.high) }
+    }
+
+    fn each(self, f: impl Fn(F32x4) -> F32x4) -> Self {
+        Self { low: f(self.low), high: f(self.high) }
+    }
+}
+
+impl Add for F32x8 {
+    type Output = F32x8;
+    fn add(self, other: F32x8) -> F32x8 {
+        self.pair(other, F32x4::add)
+    }
+}
+
+impl Sub for F32x8 {
+    type Output = F32x8;
+    fn sub(self, other: F32x8) -> F32x8 {
+        self.pair(other, F32x4::sub)
+    }
+}
+
+impl Mul for F32x8 {
+    type Output = F32x8;
+    fn mul(self, other: F32x8) -> F32x8 {
+        self.pair(other, F32x4::mul)
+    }
+}
+
+impl Simd for F32x8 {
+    const LANES: usize = 8;
+    type Array = [f32; 8];
+    type Ids = [u32; 8];
+
+    fn splat(value: f32) -> Self {
+        Self { low: F32x4::splat(value), high: F32x4::splat(value) }
+    }
+
+    fn from_array(values: [f32; 8]) -> Self {
+        let (low, high) = values.split_at(4);
+        Self { low: F32x4::from_array(low.try_into().unwrap()), high: F32x4::from_array(high.try_into().unwrap()) }
+    }
+
+    fn to_array(self) -> [f32; 8] {
+        let mut values = [0.0; 8];
+        values[..4].copy_from_slice(&self.low.to_array());
+        values[4..].copy_from_slice(&self.high.to_array());
+        values
+    }
+
+    fn min(self, other: Self) -> Self {
+        self.pair(other, F32x4::min)
+    }
+
+    fn max(self, other: Self) -> Self {
+        self.pair(other, F32x4::max)
+    }
+
+    fn sqrt(self) -> Self {
+        self.each(F32x4::sqrt)
+    }
+
+    fn abs(self) -> Self {
+        self.each(F32x4::abs)
+    }
+
+    fn floor(self) -> Self {
+        self.each(F32x4::floor)
+    }
+
+    fn lt(self, other: Self) -> Self {
+        self.pair(other, F32x4::lt)
+    }
+
+    fn gt(self, other: Self) -> Self {
+        self.pair(other, F32x4::gt)
+    }
+
+    fn ge(self, other: Self) -> Self {
+        self.pair(other, F32x4::ge)
+    }
+
+    fn and(self, other: Self) -> Self {
+        self.pair(other, F32x4::and)
+    }
+
+    fn or(self, other: Self) -> Self {
+        self.pair(other, F32x4::or)
+    }
+
+    fn not(self) -> Self {
+        self.each(F32x4::not)
+    }
+
+    fn select(mask: Self, a: Self, b: Self) -> Self {
+        Self {
+            low: F32x4::select(mask.low, a.low, b.low),
+            high: F32x4::select(mask.high, a.high, b.high),
+        }
+    }
+
+    fn bitmask(self) -> u32 {
+        self.low.bitmask() | self.high.bitmask() << 4
+    }
+}
+
+/// Lote de puntos 3D como estructura de arreglos
+#[derive(Clone, Copy, Debug)]
+pub struct Vec3Batch<S: Simd> {
+    pub x: S,
+    pub y: S,
+    pub z: S,
+}
+
+impl<S: Simd> Vec3Batch<S> {
+    pub fn new(x: S, y: S, z: S) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn splat(v: Vec3) -> Self {
+        Self::new(S::splat(v.x), S::splat(v.y), S::splat(v.z))
+    }
+
+    /// Lote con el punto que `f` da para cada carril
+    pub fn from_fn(mut f: impl FnMut(usize) -> Vec3) -> Self {
+        let mut points = (S::Array::default(), S::Array::default(), S::Array::default());
+        for i in 0..S::LANES {
+            let p = f(i);
+            points.0.as_mut()[i] = p.x;
+            points.1.as_mut()[i] = p.y;
+            points.2.as_mut()[i] = p.z;
+        }
+        Self::new(S::from_array(points.0), S::from_array(points.1), S::from_array(points.2))
+    }
+
+    pub fn lane(&self, index: usize) -> Vec3 {
+        Vec3::new(self.x.lane(index), self.y.lane(index), self.z.lane(index))
+    }
+
+    pub fn dot(self, other: Self) -> S {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn floor(self) -> Self {
+        Self::new(self.x.floor(), self.y.floor(), self.z.floor())
+    }
+
+    /// Por carril, el punto de `a` donde `mask` está activa y el de `b` donde no
+    pub fn select(mask: S, a: Self, b: Self) -> Self {
+        Self::new(S::select(mask, a.x, b.x), S::select(mask, a.y, b.y), S::select(mask, a.z, b.z))
+    }
+
+    /// Cada punto escalado por su carril de `factor`
+    pub fn scale(self, factor: S) -> Self {
+        Self::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+}
+
+impl<S: Simd> Add for Vec3Batch<S> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl<S: Simd> Sub for Vec3Batch<S> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+/// Resultado de `cellular_batch`: lo mismo que `CellularSample` para cada carril
+#[derive(Clone, Copy, Debug)]
+pub struct CellularBatch<S: Simd> {
+    pub f1: S,
+    pub f2: S,
+    pub feature_point: Option<Vec3Batch<S>>,
+    pub second_point: Option<Vec3Batch<S>>,
+    pub cell_id: S::Ids,
+}
+
+impl<S: Simd> CellularBatch<S> {
+    /// Lote con la muestra que `f` da para cada carril
+    /// (los puntos característicos solo se guardan si todas las muestras los tienen)
+    pub fn from_fn(mut f: impl FnMut(usize) -> CellularSample) -> Self {
+        let (mut f1, mut f2) = (S::Array::default(), S::Array::default());
+        let mut cell_id = S::Ids::default();
+        let mut points: [(S::Array, S::Array, S::Array); 2] = Default::default();
+        let mut has_points = true;
+        for lane in 0..S::LANES {
+            let sample = f(lane);
+            f1.as_mut()[lane] = sample.f1;
+            f2.as_mut()[lane] = sample.f2;
+            cell_id.as_mut()[lane] = sample.cell_id;
+            match (sample.feature_point, sample.second_point) {
+                (Some(first), Some(second)) => {
+                    for (point, p) in points.iter_mut().zip([first, second]) {
+                        point.0.as_mut()[lane] = p.x;
+                        point.1.as_mut()[lane] = p.y;
+                        point.2.as_mut()[lane] = p.z;
+                    }
+                }
+                _ => has_points = false,
+            }
+        }
+        let [first, second] = points.map(|(x, y, z)| Vec3Batch::new(S::from_array(x), S::from_array(y), S::from_array(z)));
+        Self {
+            f1: S::from_array(f1),
+            f2: S::from_array(f2),
+            feature_point: has_points.then_some(first),
+            second_point: has_points.then_some(second),
+            cell_id,
+        }
+    }
+
+    pub fn sample(&self, lane: usize) -> CellularSample {
+        CellularSample {
+            f1: self.f1.lane(lane),
+            f2: self.f2.lane(lane),
+            feature_point: self.feature_point.map(|point| point.lane(lane)),
+            second_point: self.second_point.map(|point| point.lane(lane)),
+            cell_id: self.cell_id.as_ref()[lane],
+        }
+    }
+}
+
+fn fade<S: Simd>(t: S) -> S {
+    t * t * t * (t * (t * S::splat(6.0) - S::splat(15.0)) + S::splat(10.0))
+}
+
+fn lerp<S: Simd>(a: S, b: S, t: S) -> S {
+    a + t * (b - a)
+}
+
+/// Núcleo de una esquina de simplex: (0.5 - d²)⁴ · (g · d), 0 fuera del símplice
+fn corner<S: Simd>(distance_squared: S, dot: S) -> S {
+    let t = (S::splat(0.5) - distance_squared).max(S::splat(0.0));
+    let t2 = t * t;
+    t2 * t2 * dot
+}
+
+/// Índices enteros de cada carril (las coordenadas ya redondeadas)
+fn integers<S: Simd>(values: S) -> impl Fn(usize) -> i32 {
+    let values = values.to_array();
+    move |i| values.as_ref()[i] as i32
+}
+
+impl NoiseGenerator {
+    /// `perlin` para todos los carriles de `p`
+    pub fn perlin_batch<S: Simd>(&self, p: &Vec3Batch<S>) -> S {
+        self.perlin_periodic_batch(p, [0; 3])
+    }
+
+    /// `perlin_periodic` para todos los carriles de `p`
+    pub fn perlin_periodic_batch<S: Simd>(&self, p: &Vec3Batch<S>, period: [u32; 3]) -> S {
+        let cell = p.floor();
+        let f = *p - cell;
+        let (u, v, w) = (fade(f.x), fade(f.y), fade(f.z));
+        // Índices envueltos de las dos esquinas de cada eje, por carril (hasta 8 carriles)
+        let corners = |values: S, period: u32| {
+            let values = integers(values);
+            let mut wrapped = [[0; 2]; 8];
+            for (lane, corner) in wrapped.iter_mut().enumerate().take(S::LANES) {
+                *corner = [wrap(values(lane), period), wrap(values(lane) + 1, period)];
+            }
+            wrapped
+        };
+        let (xs, ys, zs) = (corners(cell.x, period[0]), corners(cell.y, period[1]), corners(cell.z, period[2]));
+
+        // Producto punto del gradiente de cada esquina con el vector hacia ella
+        let grad = |dx: usize, dy: usize, dz: usize| {
+            let mut g = (S::Array::default(), S::Array::default(), S::Array::default());
+            for i in 0..S::LANES {
+                let hash = self.hash_index(xs[i][dx], ys[i][dy], zs[i][dz]);
+                let gradient = GRADIENTS[(hash & 15) as usize];
+                g.0.as_mut()[i] = gradient[0];
+                g.1.as_mut()[i] = gradient[1];
+                g.2.as_mut()[i] = gradient[2];
+            }
+            let d = f - Vec3Batch::splat(Vec3::new(dx as f32, dy as f32, dz as f32));
+            S::from_array(g.0) * d.x + S::from_array(g.1) * d.y + S::from_array(g.2) * d.z
+        };
+
+        let x1 = lerp(grad(0, 0, 0), grad(1, 0, 0), u);
+        let x2 = lerp(grad(0, 1, 0), grad(1, 1, 0), u);
+        let y1 = lerp(x1, x2, v);
+        let x3 = lerp(grad(0, 0, 1), grad(1, 0, 1), u);
+        let x4 = lerp(grad(0, 1, 1), grad(1, 1, 1), u);
+        let y2 = lerp(x3, x4, v);
+
+        ((lerp(y1, y2, w) + S::splat(1.0)) * S::splat(0.5)).clamp(0.0, 1.0)
+    }
+
+    /// `turbulence` para todos los carriles de `p`
+    #[allow(dead_code)]
+    pub fn turbulence_batch<S: Simd>(&self, p: &Vec3Batch<S>, octaves: i32) -> S {
+        let mut value = S::splat(0.0);
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+
+        for _ in 0..octaves {
+            value = value + S::splat(amplitude) * self.perlin_batch(&p.scale(S::splat(frequency)));
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+
+        value
+    }
+
+    /// `turbulence_periodic` para todos los carriles de `p`
+    pub fn turbulence_periodic_batch<S: Simd>(&self, p: &Vec3Batch<S>, octaves: i32, period: [u32; 3]) -> S {
+        let mut value = S::splat(0.0);
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        let mut period = period;
+
+        for _ in 0..octaves {
+            value = value + S::splat(amplitude) * self.perlin_periodic_batch(&p.scale(S::splat(frequency)), period);
+            amplitude *= 0.5;
+            frequency *= 2.0;
+            period = period.map(|p| p * 2);
+        }
+
+        value
+    }
+
+    /// `turbulence_slices` para todos los carriles de `p` (con el mismo `w` en todos)
+    pub fn turbulence_slices_batch<S: Simd>(&self, p: &Vec3Batch<S>, w: f32, octaves: i32, period: u32) -> S {
+        let [(a, weight_a), (b, weight_b)] = time_slices(w, period as f32);
+        let slice = |offset: Vec3| self.turbulence_periodic_batch(&(*p + Vec3Batch::splat(offset)), octaves, [period; 3]);
+        slice(a) * S::splat(weight_a) + slice(b) * S::splat(weight_b)
+    }
+
+    /// `simplex3` para todos los carriles de `p`
+    #[allow(dead_code)]
+    pub fn simplex3_batch<S: Simd>(&self, p: &Vec3Batch<S>) -> S {
+        let s = (p.x + p.y + p.z) * S::splat(F3);
+        let cell = Vec3Batch::new(p.x + s, p.y + s, p.z + s).floor();
+        let t = (cell.x + cell.y + cell.z) * S::splat(G3);
+        let p0 = Vec3Batch::new(p.x - (cell.x - t), p.y - (cell.y - t), p.z - (cell.z - t));
+
+        // Las ramas que eligen el tetraedro, como máscaras: las esquinas intermedias
+        // avanzan en los ejes de las coordenadas mayores
+        let x_ge_y = p0.x.ge(p0.y);
+        let y_ge_z = p0.y.ge(p0.z);
+        let x_ge_z = p0.x.ge(p0.z);
+        let first = Vec3Batch::new(x_ge_y.and(x_ge_z).ones(), x_ge_y.not().and(y_ge_z).ones(), x_ge_z.or(y_ge_z).not().ones());
+        let second = Vec3Batch::new(x_ge_y.or(x_ge_z).ones(), x_ge_y.not().or(y_ge_z).ones(), x_ge_z.and(y_ge_z).not().ones());
+        let offsets = [Vec3Batch::splat(Vec3::ZERO), first, second, Vec3Batch::splat(Vec3::ONE)];
+
+        let (ii, jj, kk) = (integers(cell.x), integers(cell.y), integers(cell.z));
+        let mut sum = S::splat(0.0);
+        for (n, offset) in offsets.into_iter().enumerate() {
+            let unskew = S::splat(n as f32 * G3);
+            let d = Vec3Batch::new(p0.x - offset.x + unskew, p0.y - offset.y + unskew, p0.z - offset.z + unskew);
+
+            let (oi, oj, ok) = (integers(offset.x), integers(offset.y), integers(offset.z));
+            let mut g = (S::Array::default(), S::Array::default(), S::Array::default());
+            for i in 0..S::LANES {
+                let hash = self.permute(
+                    (ii(i) & 255) + oi(i)
+                        + self.permute((jj(i) & 255) + oj(i) + self.permute((kk(i) & 255) + ok(i)) as i32) as i32,
+                );
+                let gradient = GRADIENTS[hash & 15];
+                g.0.as_mut()[i] = gradient[0];
+                g.1.as_mut()[i] = gradient[1];
+                g.2.as_mut()[i] = gradient[2];
+            }
+            let g = Vec3Batch::new(S::from_array(g.0), S::from_array(g.1), S::from_array(g.2));
+            sum = sum + corner(d.dot(d), g.dot(d));
+        }
+        ((sum * S::splat(SCALE_3D) + S::splat(1.0)) * S::splat(0.5)).clamp(0.0, 1.0)
+    }
+
+    /// `simplex4` para todos los carriles de `p` y `w`
+    pub fn simplex4_batch<S: Simd>(&self, p: &Vec3Batch<S>, w: S) -> S {
+        let s = (p.x + p.y + p.z + w) * S::splat(F4);
+        let cell = [(p.x + s).floor(), (p.y + s).floor(), (p.z + s).floor(), (w + s).floor()];
+        let t = (cell[0] + cell[1] + cell[2] + cell[3]) * S::splat(G4);
+        let p0 = [p.x - (cell[0] - t), p.y - (cell[1] - t), p.z - (cell[2] - t), w - (cell[3] - t)];
+
+        // rank[c]: cuántas coordenadas son menores que la c-ésima
+        let mut rank = [S::splat(0.0); 4];
+        for a in 0..4 {
+            for b in a + 1..4 {
+                let greater = p0[a].gt(p0[b]);
+                rank[a] = rank[a] + greater.ones();
+                rank[b] = rank[b] + greater.not().ones();
+            }
+        }
+
+        let base = cell.map(integers);
+        let mut sum = S::splat(0.0);
+        for n in 0..5 {
+            // La esquina n avanza en las coordenadas con rango >= 4 - n
+            let threshold = S::splat((4 - n) as f32);
+            let offset = rank.map(|r| r.ge(threshold).ones());
+            let unskew = S::splat(n as f32 * G4);
+            let c: [S; 4] = std::array::from_fn(|d| p0[d] - offset[d] + unskew);
+
+            let o = offset.map(integers);
+            let mut g: [S::Array; 4] = Default::default();
+            for i in 0..S::LANES {
+                let lattice: [i32; 4] = std::array::from_fn(|d| (base[d](i) & 255) + o[d](i));
+                let hash = self.permute(
+                    lattice[0]
+                        + self.permute(lattice[1] + self.permute(lattice[2] + self.permute(lattice[3]) as i32) as i32)
+                            as i32,
+                );
+                let gradient = GRADIENTS_4D[hash & 31];
+                for (component, value) in g.iter_mut().zip(gradient) {
+                    component.as_mut()[i] = value;
+                }
+            }
+            let g = g.map(S::from_array);
+            let distance_squared = c[0] * c[0] + c[1] * c[1] + c[2] * c[2] + c[3] * c[3];
+            sum = sum + corner(distance_squared, g[0] * c[0] + g[1] * c[1] + g[2] * c[2] + g[3] * c[3]);
+        }
+        ((sum * S::splat(SCALE_4D) + S::splat(1.0)) * S::splat(0.5)).clamp(0.0, 1.0)
+    }
+
+    /// `turbulence4` para todos los carriles de `p` y `w`
+    #[allow(dead_code)]
+    pub fn turbulence4_batch<S: Simd>(&self, p: &Vec3Batch<S>, w: S, octaves: i32) -> S {
+        let mut value = S::splat(0.0);
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+
+        for _ in 0..octaves {
+            let f = S::splat(frequency);
+            value = value + S::splat(amplitude) * self.simplex4_batch(&p.scale(f), w * f);
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+
+        value
+    }
+
+    /// `cellular_sample` para todos los carriles de `p`
+    #[allow(dead_code)]
+    pub fn cellular_batch<S: Simd>(&self, p: &Vec3Batch<S>, metric: DistanceMetric, jitter: f32) -> CellularBatch<S> {
+        self.cellular_periodic_batch(p, metric, jitter, 0)
+    }
+
+    /// `cellular_sample_periodic` para todos los carriles de `p`
+    pub fn cellular_periodic_batch<S: Simd>(&self, p: &Vec3Batch<S>, metric: DistanceMetric, jitter: f32, period: u32) -> CellularBatch<S> {
+        let jitter = S::splat(jitter.clamp(0.0, 1.0));
+        let cell = p.floor();
+        // Índices envueltos de las tres celdas vecinas de cada eje, por carril (hasta 8 carriles)
+        let neighbors = |values: S| {
+            let values = integers(values);
+            let mut wrapped = [[0; 3]; 8];
+            for (lane, cells) in wrapped.iter_mut().enumerate().take(S::LANES) {
+                *cells = [-1, 0, 1].map(|d| if period == 0 { values(lane) + d } else { wrap(values(lane) + d, period) });
+            }
+            wrapped
+        };
+        let (xs, ys, zs) = (neighbors(cell.x), neighbors(cell.y), neighbors(cell.z));
+
+        let mut nearest = CellularBatch {
+            f1: S::splat(f32::MAX),
+            f2: S::splat(f32::MAX),
+            feature_point: None,
+            second_point: None,
+            cell_id: S::Ids::default(),
+        };
+        let (mut point, mut second) = (Vec3Batch::splat(Vec3::ZERO), Vec3Batch::splat(Vec3::ZERO));
+
+        for i in -1..=1 {
+            for j in -1..=1 {
+                for k in -1..=1 {
+                    // Identificador y desplazamiento del punto de la celda vecina, por carril
+                    let mut ids = S::Ids::default();
+                    let mut offset = (S::Array::default(), S::Array::default(), S::Array::default());
+                    for lane in 0..S::LANES {
+                        let id = self.cell_id(xs[lane][(i + 1) as usize], ys[lane][(j + 1) as usize], zs[lane][(k + 1) as usize]);
+                        let [a, b, c, _] = id.to_le_bytes();
+                        ids.as_mut()[lane] = id;
+                        offset.0.as_mut()[lane] = a as f32 / 255.0 - 0.5;
+                        offset.1.as_mut()[lane] = b as f32 / 255.0 - 0.5;
+                        offset.2.as_mut()[lane] = c as f32 / 255.0 - 0.5;
+                    }
+                    let offset = Vec3Batch::new(S::from_array(offset.0), S::from_array(offset.1), S::from_array(offset.2));
+                    let neighbor = cell + Vec3Batch::splat(Vec3::new(i as f32, j as f32, k as f32));
+                    let feature = neighbor + Vec3Batch::splat(Vec3::splat(0.5)) + offset.scale(jitter);
+
+                    let d = *p - feature;
+                    let distance = match metric {
+                        DistanceMetric::Euclidean => d.dot(d).sqrt(),
+                        DistanceMetric::Manhattan => d.x.abs() + d.y.abs() + d.z.abs(),
+                        DistanceMetric::Chebyshev => d.x.abs().max(d.y.abs()).max(d.z.abs()),
+                    };
+
+                    // Más cerca que F1: el anterior F1 pasa a ser F2; si no, puede bajar F2
+                    let closer = distance.lt(nearest.f1);
+                    let second_closer = closer.not().and(distance.lt(nearest.f2));
+                    nearest.f2 = S::select(closer, nearest.f1, nearest.f2.min(distance));
+                    nearest.f1 = S::select(closer, distance, nearest.f1);
+                    second = Vec3Batch::select(closer, point, Vec3Batch::select(second_closer, feature, second));
+                    point = Vec3Batch::select(closer, feature, point);
+                    let bits = closer.bitmask();
+                    for lane in (0..S::LANES).filter(|lane| bits & (1 << lane) != 0) {
+                        nearest.cell_id.as_mut()[lane] = ids.as_ref()[lane];
+                    }
+                }
+            }
+        }
+
+        nearest.feature_point = Some(point);
+        nearest.second_point = Some(second);
+        nearest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::test_points::points;
+
+    /// Lotes con los puntos de prueba compartidos (uno de cada siete, sobre las celdas enteras)
+    fn batches<S: Simd>(count: usize) -> Vec<Vec3Batch<S>> {
+        let mut points = points(count * S::LANES);
+        (0..count)
+            .map(|n| {
+                Vec3Batch::from_fn(|_| {
+                    let p = points.next().unwrap();
+                    if n % 7 == 0 { Vec3::new(p.x.floor(), p.y.floor(), p.z.floor()) } else { p }
+                })
+            })
+            .collect()
+    }
+
+    fn assert_close(batch: f32, scalar: f32) {
+        assert!((batch - scalar).abs() < 1e-5, "lote {batch} vs escalar {scalar}");
+    }
+
+    #[test]
+    fn lanes_behave_like_scalars() {
+        let a = F32x4::from_array([-1.5, -0.0, 2.0, 3.5]);
+        let b = F32x4::splat(2.0);
+        assert_eq!(a.floor().to_array(), [-2.0, -0.0, 2.0, 3.0]);
+        assert_eq!(a.abs().to_array(), [1.5, 0.0, 2.0, 3.5]);
+        assert_eq!((a * b - b).to_array(), [-5.0, -2.0, 2.0, 5.0]);
+        assert_eq!(a.lt(b).bitmask(), 0b0011);
+        assert_eq!(a.ge(b).not().bitmask(), 0b0011);
+        assert_eq!(F32x4::select(a.gt(b), a, b).to_array(), [2.0, 2.0, 2.0, 3.5]);
+
+        let wide = F32x8::from_fn(|i| i as f32 - 3.5);
+        assert_eq!(wide.floor().to_array(), [-4.0, -3.0, -2.0, -1.0, 0.0, 1.0, 2.0, 3.0]);
+        assert_eq!(wide.gt(F32x8::splat(0.0)).bitmask(), 0b1111_0000);
+    }
+
+    #[test]
+    fn perlin_and_simplex_batches_match_the_scalar_noise() {
+        let noise = NoiseGenerator::new(23);
+        for p in batches::<F32x4>(200) {
+            let (perlin, simplex) = (noise.perlin_batch(&p), noise.simplex3_batch(&p));
+            let w = p.x * F32x4::splat(0.3);
+            let (turbulence, simplex4) = (noise.turbulence_batch(&p, 4), noise.turbulence4_batch(&p, w, 3));
+            let slices = noise.turbulence_slices_batch(&p, -1.3, 4, 8);
+            for i in 0..4 {
+                let q = p.lane(i);
+                assert_close(perlin.lane(i), noise.perlin(q.x, q.y, q.z));
+                assert_close(simplex.lane(i), noise.simplex3(q.x, q.y, q.z));
+                assert_close(turbulence.lane(i), noise.turbulence(q.x, q.y, q.z, 4));
+                assert_close(simplex4.lane(i), noise.turbulence4(q.x, q.y, q.z, w.lane(i), 3));
+                assert_close(slices.lane(i), noise.turbulence_slices(q, -1.3, 4, 8));
+            }
+        }
+    }
+
+    #[test]
+    fn cellular_batches_match_the_scalar_noise() {
+        let noise = NoiseGenerator::new(31);
+        for p in batches::<F32x8>(100) {
+            for metric in [DistanceMetric::Euclidean, DistanceMetric::Manhattan, DistanceMetric::Chebyshev] {
+                let batch = noise.cellular_batch(&p, metric, 0.9);
+                for i in 0..8 {
+                    let (sample, scalar) = (batch.sample(i), noise.cellular_sample(p.lane(i), metric, 0.9));
+                    assert_close(sample.f1, scalar.f1);
+                    assert_close(sample.f2, scalar.f2);
+                    assert!((sample.feature_point.unwrap() - scalar.feature_point.unwrap()).length() < 1e-5);
+                    assert!((sample.second_point.unwrap() - scalar.second_point.unwrap()).length() < 1e-5);
+                    assert_eq!(sample.cell_id, scalar.cell_id);
+                }
+            }
+            let batch = noise.cellular_periodic_batch(&p, DistanceMetric::Euclidean, 1.0, 5);
+            for i in 0..8 {
+                let scalar = noise.cellular_sample_periodic(p.lane(i), DistanceMetric::Euclidean, 1.0, 5);
+                assert_close(batch.sample(i).f1, scalar.f1);
+                assert_eq!(batch.sample(i).cell_id, scalar.cell_id);
+            }
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//! SIMPLEX NOISE (2D, 3D y 4D)
+//! En lugar de la rejilla de cubos de Perlin, el espacio se divide en símplices
+//! (triángulos, tetraedros, pentácoros): cada punto depende de n + 1 esquinas en vez
+//! de 2ⁿ, cada esquina aporta un núcleo radial sin interpolar entre caras, y no hay
+//! direcciones privilegiadas. En 4D cuesta 5 esquinas en lugar de 16, lo que permite
+//! usar el tiempo como cuarta dimensión: el patrón evoluciona en su sitio en vez de
+//! deslizarse como cuando se suma `time` a una coordenada
+//!
+//! Las salidas están en [0, 1] con media 0.5, igual que `perlin`
+
+use std::f32::consts::FRAC_1_SQRT_2;
+use crate::math::Vec3;
+use super::{NoiseGenerator, GRADIENTS};
+
+/// Direcciones para 2D: 8 vectores unitarios separados 45°
+const GRADIENTS_2D: [[f32; 2]; 8] = [
+    [1.0, 0.0], [-1.0, 0.0], [0.0, 1.0], [0.0, -1.0],
+    [FRAC_1_SQRT_2, FRAC_1_SQRT_2], [-FRAC_1_SQRT_2, FRAC_1_SQRT_2],
+    [FRAC_1_SQRT_2, -FRAC_1_SQRT_2], [-FRAC_1_SQRT_2, -FRAC_1_SQRT_2],
+];
+
+/// Direcciones para 4D: los 32 vectores hacia el centro de las aristas de un hipercubo
+/// (todas las permutaciones de (0, ±1, ±1, ±1))
+pub(super) const GRADIENTS_4D: [[f32; 4]; 32] = [
+    [0.0, 1.0, 1.0, 1.0], [0.0, 1.0, 1.0, -1.0], [0.0, 1.0, -1.0, 1.0], [0.0, 1.0, -1.0, -1.0],
+    [0.0, -1.0, 1.0, 1.0], [0.0, -1.0, 1.0, -1.0], [0.0, -1.0, -1.0, 1.0], [0.0, -1.0, -1.0, -1.0],
+    [1.0, 0.0, 1.0, 1.0], [1.0, 0.0, 1.0, -1.0], [1.0, 0.0, -1.0, 1.0], [1.0, 0.0, -1.0, -1.0],
+    [-1.0, 0.0, 1.0, 1.0], [-1.0, 0.0, 1.0, -1.0], [-1.0, 0.0, -1.0, 1.0], [-1.0, 0.0, -1.0, -1.0],
+    [1.0, 1.0, 0.0, 1.0], [1.0, 1.0, 0.0, -1.0], [1.0, -1.0, 0.0, 1.0], [1.0, -1.0, 0.0, -1.0],
+    [-1.0, 1.0, 0.0, 1.0], [-1.0, 1.0, 0.0, -1.0], [-1.0, -1.0, 0.0, 1.0], [-1.0, -1.0, 0.0, -1.0],
+    [1.0, 1.0, 1.0, 0.0], [1.0, 1.0, -1.0, 0.0], [1.0, -1.0, 1.0, 0.0], [1.0, -1.0, -1.0, 0.0],
+    [-1.0, 1.0, 1.0, 0.0], [-1.0, 1.0, -1.0, 0.0], [-1.0, -1.0, 1.0, 0.0], [-1.0, -1.0, -1.0, 0.0],
+];
+
+// Factores para pasar de la rejilla de símplices a la cúbica (skew) y de vuelta (unskew)
+// F = (√(n+1) - 1) / n,  G = (n + 1 - √(n+1)) / (n (n+1))
+const F2: f32 = 0.366_025_4;
+const G2: f32 = 0.211_324_87;
+pub(super) const F3: f32 = 1.0 / 3.0;
+pub(super) const G3: f32 = 1.0 / 6.0;
+pub(super) const F4: f32 = 0.309_017;
+pub(super) const G4: f32 = 0.138_196_6;
+
+// Escalas que llevan la suma de las contribuciones a [-1, 1]
+// (núcleo (r² - d²)⁴ con r² = 0.5, que se anula en el borde de cada símplice;
+// los máximos se midieron con millones de muestras)
+const SCALE_2D: f32 = 99.0;
+pub(super) const SCALE_3D: f32 = 76.0;
+pub(super) const SCALE_4D: f32 = 62.0;
+
+/// Aporte de una esquina: núcleo radial por el producto punto con su gradiente
+fn corner(distance_squared: f32, dot: f32) -> f32 {
+    let t = 0.5 - distance_squared;
+    if t <= 0.0 {
+        0.0
+    } else {
+        let t2 = t * t;
+        t2 * t2 * dot
+    }
+}
+
+/// Lleva el ruido crudo en [-1, 1] a [0, 1]
+fn to_unit(raw: f32) -> f32 {
+    ((raw + 1.0) * 0.5).clamp(0.0, 1.0)
+}
+
+impl NoiseGenerator {
+    pub(super) fn permute(&self, index: i32) -> usize {
+        self.permutation[(index & 511) as usize] as usize
+    }
+
+    /// Simplex 2D
+    #[allow(dead_code)]
+    pub fn simplex2(&self, x: f32, y: f32) -> f32 {
+        // Celda de la rejilla sesgada que contiene el punto
+        let s = (x + y) * F2;
+        let i = (x + s).floor();
+        let j = (y + s).floor();
+        let t = (i + j) * G2;
+        let x0 = x - (i - t);
+        let y0 = y - (j - t);
+
+        // El triángulo inferior o superior de la celda según qué coordenada domina
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+        let corners = [
+            (0, 0, x0, y0),
+            (i1, j1, x0 - i1 as f32 + G2, y0 - j1 as f32 + G2),
+            (1, 1, x0 - 1.0 + 2.0 * G2, y0 - 1.0 + 2.0 * G2),
+        ];
+
+        let (ii, jj) = (i as i32 & 255, j as i32 & 255);
+        let mut sum = 0.0;
+        for (di, dj, cx, cy) in corners {
+            let hash = self.permute(ii + di + self.permute(jj + dj) as i32);
+            let g = GRADIENTS_2D[hash & 7];
+            sum += corner(cx * cx + cy * cy, g[0] * cx + g[1] * cy);
+        }
+        to_unit(sum * SCALE_2D)
+    }
+
+    /// Las 4 esquinas del tetraedro que contiene (x, y, z): vector desde cada esquina
+    /// hasta el punto y gradiente de la esquina
+    fn simplex3_corners(&self, x: f32, y: f32, z: f32) -> [(Vec3, Vec3); 4] {
+        let s = (x + y + z) * F3;
+        let i = (x + s).floor();
+        let j = (y + s).floor();
+        let k = (z + s).floor();
+        let t = (i + j + k) * G3;
+        let x0 = x - (i - t);
+        let y0 = y - (j - t);
+        let z0 = z - (k - t);
+
+        // El cubo sesgado se parte en 6 tetraedros: el orden de las coordenadas elige uno
+        let (first, second) = if x0 >= y0 {
+            if y0 >= z0 {
+                ([1, 0, 0], [1, 1, 0])
+            } else if x0 >= z0 {
+                ([1, 0, 0], [1, 0, 1])
+            } else {
+                ([0, 0, 1], [1, 0, 1])
+            }
+        } else if y0 < z0 {
+            ([0, 0, 1], [0, 1, 1])
+        } else if x0 < z0 {
+            ([0, 1, 0], [0, 1, 1])
+        } else {
+            ([0, 1, 0], [1, 1, 0])
+        };
+
+        let offsets = [[0, 0, 0], first, second, [1, 1, 1]];
+        let (ii, jj, kk) = (i as i32 & 255, j as i32 & 255, k as i32 & 255);
+        offsets.map(|offset| {
+            let unskew = (offset[0] + offset[1] + offset[2]) as f32 * G3;
+            let d = Vec3::new(
+                x0 - offset[0] as f32 + unskew,
+                y0 - offset[1] as f32 + unskew,
+                z0 - offset[2] as f32 + unskew,
+            );
+            let hash = self.permute(
+                ii + offset[0] + self.permute(jj + offset[1] + self.permute(kk + offset[2]) as i32) as i32,
+            );
+            let g = GRADIENTS[hash & 15];
+            (d, Vec3::new(g[0], g[1], g[2]))
+        })
+    }
+
+    /// Simplex 3D
+    pub fn simplex3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let sum: f32 = self
+            .simplex3_corners(x, y, z)
+            .iter()
+            .map(|&(d, g)| corner(d.dot(d), g.dot(d)))
+            .sum();
+        to_unit(sum * SCALE_3D)
+    }
+
+    /// Simplex 3D con su gradiente analítico: (valor en [0, 1], ∂valor/∂p)
+    /// Cada esquina aporta t⁴·(g·d) con t = 0.5 - |d|², cuya derivada es
+    /// t⁴·g - 8·t³·(g·d)·d
+    #[allow(dead_code)]
+    pub fn simplex3_derivative(&self, x: f32, y: f32, z: f32) -> (f32, Vec3) {
+        let mut sum = 0.0;
+        let mut gradient = Vec3::ZERO;
+        for (d, g) in self.simplex3_corners(x, y, z) {
+            let t = 0.5 - d.dot(d);
+            if t > 0.0 {
+                let t3 = t * t * t;
+                let dot = g.dot(d);
+                sum += t3 * t * dot;
+                gradient += g * (t3 * t) - d * (8.0 * t3 * dot);
+            }
+        }
+        (to_unit(sum * SCALE_3D), gradient * (SCALE_3D * 0.5))
+    }
+
+    /// Simplex 4D: con `w = time * velocidad` el patrón 3D evoluciona sin desplazarse
+    pub fn simplex4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 {
+        let s = (x + y + z + w) * F4;
+        let i = (x + s).floor();
+        let j = (y + s).floor();
+        let k = (z + s).floor();
+        let l = (w + s).floor();
+        let t = (i + j + k + l) * G4;
+        let p0 = [x - (i - t), y - (j - t), z - (k - t), w - (l - t)];
+
+        // El orden de magnitud de las coordenadas elige uno de los 24 símplices:
+        // rank[c] cuenta cuántas coordenadas son menores que la c-ésima
+        let mut rank = [0; 4];
+        for a in 0..4 {
+            for b in a + 1..4 {
+                if p0[a] > p0[b] {
+                    rank[a] += 1;
+                } else {
+                    rank[b] += 1;
+                }
+            }
+        }
+
+        let base = [i as i32 & 255, j as i32 & 255, k as i32 & 255, l as i32 & 255];
+        let mut sum = 0.0;
+        for n in 0..5 {
+            // La esquina n avanza en las coordenadas con rango >= 4 - n
+            let offset: [i32; 4] = std::array::from_fn(|c| (rank[c] >= 4 - n) as i32);
+            let unskew = n as f32 * G4;
+            let c: [f32; 4] = std::array::from_fn(|d| p0[d] - offset[d] as f32 + unskew);
+
+            let hash = self.permute(
+                base[0] + offset[0]
+                    + self.permute(
+                        base[1] + offset[1]
+                            + self.permute(base[2] + offset[2] + self.permute(base[3] + offset[3]) as i32) as i32,
+                    ) as i32,
+            );
+            let g = GRADIENTS_4D[hash & 31];
+            let distance_squared = c[0] * c[0] + c[1] * c[1] + c[2] * c[2] + c[3] * c[3];
+            sum += corner(distance_squared, g[0] * c[0] + g[1] * c[1] + g[2] * c[2] + g[3] * c[3]);
+        }
+        to_unit(sum * SCALE_4D)
+    }
+
+    /// Turbulencia con simplex 4D (mismas octavas y pesos que `turbulence`)
+    pub fn turbulence4(&self, x: f32, y: f32, z: f32, w: f32, octaves: i32) -> f32 {
+        let mut value = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+
+        for _ in 0..octaves {
+            value += amplitude * self.simplex4(x * frequency, y * frequency, z * frequency, w * frequency);
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::test_points::points;
+
+    fn statistics(values: &[f32]) -> (f32, f32, f32, f32) {
+        let n = values.len() as f32;
+        let mean = values.iter().sum::<f32>() / n;
+        let deviation = (values.iter().map(|v| (v - mean) * (v - mean)).sum::<f32>() / n).sqrt();
+        let min = values.iter().copied().fold(f32::MAX, f32::min);
+        let max = values.iter().copied().fold(f32::MIN, f32::max);
+        (mean, deviation, min, max)
+    }
+
+    #[test]
+    fn outputs_cover_the_unit_range() {
+        let generator = NoiseGenerator::new(21);
+        let mut samples = (Vec::new(), Vec::new(), Vec::new());
+        for p in points(20_000) {
+            // La cuarta coordenada mezcla las otras para no repetir ninguna
+            let (x, y, z, w) = (p.x, p.y, p.z, p.y * 1.7 - p.z * 0.6);
+            samples.0.push(generator.simplex2(x, y));
+            samples.1.push(generator.simplex3(x, y, z));
+            samples.2.push(generator.simplex4(x, y, z, w));
+        }
+
+        for values in [samples.0, samples.1, samples.2] {
+            let (mean, deviation, min, max) = statistics(&values);
+            assert!((mean - 0.5).abs() < 0.01, "media {mean}");
+            assert!((0.1..0.3).contains(&deviation), "desviación {deviation}");
+            assert!(min < 0.15 && max > 0.85, "rango [{min}, {max}]");
+        }
+    }
+
+    #[test]
+    fn noise_is_continuous() {
+        // El núcleo se anula en el borde de cada símplice: pasos pequeños, cambios pequeños
+        let generator = NoiseGenerator::new(2);
+        let step = 1e-3;
+        for i in 0..2000 {
+            let t = i as f32 * 0.0137;
+            let (x, y, z, w) = (t * 3.1, t * 1.7 - 5.0, t * 2.3, t * 0.9);
+            assert!((generator.simplex2(x + step, y) - generator.simplex2(x, y)).abs() < 0.01);
+            assert!((generator.simplex3(x, y + step, z) - generator.simplex3(x, y, z)).abs() < 0.01);
+            assert!((generator.simplex4(x, y, z, w + step) - generator.simplex4(x, y, z, w)).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn simplex3_derivative_matches_finite_differences() {
+        let generator = NoiseGenerator::new(5);
+        let h = 1e-3;
+        for i in 0..400 {
+            let t = i as f32 * 0.0731;
+            let (x, y, z) = (t * 2.3 - 9.0, (t * 1.3).sin() * 4.0, t * 0.7);
+            let (value, gradient) = generator.simplex3_derivative(x, y, z);
+            assert!((value - generator.simplex3(x, y, z)).abs() < 1e-5);
+            let numeric = Vec3::new(
+                generator.simplex3(x + h, y, z) - generator.simplex3(x - h, y, z),
+                generator.simplex3(x, y + h, z) - generator.simplex3(x, y - h, z),
+                generator.simplex3(x, y, z + h) - generator.simplex3(x, y, z - h),
+            ) / (2.0 * h);
+            assert!((gradient - numeric).length() < 0.05, "{gradient:?} vs {numeric:?}");
+        }
+    }
+
+    #[test]
+    fn fourth_dimension_evolves_the_pattern() {
+        // Al avanzar w el patrón cambia en su sitio: la correlación con el de partida cae
+        let generator = NoiseGenerator::new(9);
+        let points: Vec<(f32, f32, f32)> = (0..500).map(|i| (i as f32 * 0.31, i as f32 * 0.17, i as f32 * 0.23)).collect();
+        let difference = |dw: f32| {
+            points
+                .iter()
+                .map(|&(x, y, z)| (generator.simplex4(x, y, z, dw) - generator.simplex4(x, y, z, 0.0)).abs())
+                .sum::<f32>()
+                / points.len() as f32
+        };
+        assert!(difference(0.02) < difference(0.1));
+        assert!(difference(0.1) < difference(0.4));
+        assert!(difference(0.4) > 0.05);
+    }
+}
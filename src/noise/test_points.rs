@@ -0,0 +1,12 @@
+//! Puntos de prueba deterministas para los tests del ruido: una curva que cruza
+//! muchas celdas de la red en los tres ejes, incluidas coordenadas negativas
+
+use crate::math::Vec3;
+
+/// Los primeros `count` puntos de la curva
+pub fn points(count: usize) -> impl Iterator<Item = Vec3> {
+    (0..count).map(|i| {
+        let t = i as f32 * 0.173;
+        Vec3::new(t * 1.3 - 20.0, (t * 0.7).sin() * 9.0, t * 0.37)
+    })
+}
@@ -0,0 +1,231 @@
+//! VOLÚMENES DE RUIDO PRECALCULADOS
+//! Evaluar turbulencia de 4 octavas y la búsqueda celular de 27 celdas en cada fragmento
+//! domina el tiempo de cuadro. Como alternativa, el ruido se hornea una vez en una
+//! rejilla 3D periódica (se embaldosa sin costuras) y en el shader solo se leen 8 vóxeles
+//! con interpolación trilineal. La turbulencia exacta evoluciona con rebanadas de tiempo
+//! del mismo campo periódico (`turbulence_slices`), así que el volumen horneado dibuja
+//! el mismo patrón con el mismo movimiento; solo se pierde el detalle más fino que un
+//! vóxel, a cambio de un costo fijo por muestra
+
+use std::thread;
+use crate::math::Vec3;
+use super::periodic::time_slices;
+use super::{CellularSample, DistanceMetric, NoiseGenerator};
+
+/// Rejilla 3D periódica de `resolution`³ vóxeles que cubre `period` unidades por eje;
+/// cada vóxel guarda `N` canales
+#[derive(Clone, Debug)]
+pub struct NoiseVolume<const N: usize> {
+    resolution: usize,
+    period: f32,
+    data: Vec<[f32; N]>,
+}
+
+impl<const N: usize> NoiseVolume<N> {
+    /// Hornea `field` en la rejilla; `field` debe repetirse cada `period` unidades para
+    /// que el volumen se embaldose sin costuras. Las capas en z se reparten entre hilos
+    pub fn bake<F: Fn(Vec3) -> [f32; N] + Sync>(resolution: u32, period: f32, field: F) -> Self {
+        let resolution = resolution.max(2) as usize;
+        let voxel = period / resolution as f32;
+        let layer = resolution * resolution;
+        let mut data = vec![[0.0; N]; layer * resolution];
+
+        let threads = thread::available_parallelism().map_or(1, |n| n.get()).min(resolution);
+        let layers_per_thread = resolution.div_ceil(threads);
+        thread::scope(|scope| {
+            for (chunk, slab) in data.chunks_mut(layer * layers_per_thread).enumerate() {
+                let field = &field;
+                scope.spawn(move || {
+                    for (i, value) in slab.iter_mut().enumerate() {
+                        let index = chunk * layer * layers_per_thread + i;
+                        let (x, y, z) = (index % resolution, (index / resolution) % resolution, index / layer);
+                        *value = field(Vec3::new(x as f32, y as f32, z as f32) * voxel);
+                    }
+                });
+            }
+        });
+
+        Self { resolution, period, data }
+    }
+
+    fn voxel(&self, x: usize, y: usize, z: usize) -> &[f32; N] {
+        let n = self.resolution;
+        &self.data[(z % n) * n * n + (y % n) * n + x % n]
+    }
+
+    /// Lectura del vóxel más cercano, sin interpolar: para valores constantes por regiones
+    /// (como el valor de cada celda), que la interpolación mezclaría en los bordes
+    pub fn nearest(&self, position: Vec3) -> [f32; N] {
+        let n = self.resolution as f32;
+        let scaled = position * (n / self.period);
+        let wrap = |v: f32| v.round().rem_euclid(n) as usize;
+        *self.voxel(wrap(scaled.x), wrap(scaled.y), wrap(scaled.z))
+    }
+
+    /// Lectura con interpolación trilineal entre los 8 vóxeles vecinos (con envoltura)
+    pub fn sample(&self, position: Vec3) -> [f32; N] {
+        let n = self.resolution as f32;
+        let scaled = position * (n / self.period);
+        let base = Vec3::new(scaled.x.floor(), scaled.y.floor(), scaled.z.floor());
+        let t = scaled - base;
+        // Índices envueltos a [0, resolution)
+        let wrap = |v: f32| v.rem_euclid(n) as usize;
+        let (x, y, z) = (wrap(base.x), wrap(base.y), wrap(base.z));
+
+        let mut result = [0.0; N];
+        for (corner, weight) in [
+            ((0, 0, 0), (1.0 - t.x) * (1.0 - t.y) * (1.0 - t.z)),
+            ((1, 0, 0), t.x * (1.0 - t.y) * (1.0 - t.z)),
+            ((0, 1, 0), (1.0 - t.x) * t.y * (1.0 - t.z)),
+            ((1, 1, 0), t.x * t.y * (1.0 - t.z)),
+            ((0, 0, 1), (1.0 - t.x) * (1.0 - t.y) * t.z),
+            ((1, 0, 1), t.x * (1.0 - t.y) * t.z),
+            ((0, 1, 1), (1.0 - t.x) * t.y * t.z),
+            ((1, 1, 1), t.x * t.y * t.z),
+        ] {
+            let value = self.voxel(x + corner.0, y + corner.1, z + corner.2);
+            for (channel, v) in result.iter_mut().zip(value) {
+                *channel += v * weight;
+            }
+        }
+        result
+    }
+
+    /// Gradiente de la lectura trilineal (constante por tramos dentro de cada vóxel)
+    pub fn gradient(&self, position: Vec3) -> [Vec3; N] {
+        let n = self.resolution as f32;
+        let scaled = position * (n / self.period);
+        let base = Vec3::new(scaled.x.floor(), scaled.y.floor(), scaled.z.floor());
+        let t = scaled - base;
+        let wrap = |v: f32| v.rem_euclid(n) as usize;
+        let (x, y, z) = (wrap(base.x), wrap(base.y), wrap(base.z));
+
+        // Peso de cada extremo de un eje y su derivada
+        let weight = |corner: usize, t: f32| if corner == 1 { (t, 1.0) } else { (1.0 - t, -1.0) };
+        let mut result = [Vec3::ZERO; N];
+        for corner in 0..8 {
+            let (i, j, k) = (corner & 1, (corner >> 1) & 1, corner >> 2);
+            let ((wx, dx), (wy, dy), (wz, dz)) = (weight(i, t.x), weight(j, t.y), weight(k, t.z));
+            let slope = Vec3::new(dx * wy * wz, wx * dy * wz, wx * wy * dz) * (n / self.period);
+            for (channel, &v) in result.iter_mut().zip(self.voxel(x + i, y + j, z + k)) {
+                *channel += slope * v;
+            }
+        }
+        result
+    }
+}
+
+/// Ruido de la superficie horneado: turbulencia y ruido celular periódicos
+#[derive(Clone, Debug)]
+pub struct BakedNoise {
+    pub turbulence: NoiseVolume<1>, // Turbulencia de 4 octavas
+    pub cells: NoiseVolume<3>,      // F1, F2 y valor de la celda
+}
+
+impl BakedNoise {
+    /// Unidades que cubre el volumen de turbulencia antes de repetirse: más que el
+    /// diámetro de la esfera donde se lee (radio ~3), así no se repite sobre la superficie
+    pub const TURBULENCE_PERIOD: u32 = 8;
+    /// Octavas de la turbulencia horneada
+    pub const TURBULENCE_OCTAVES: i32 = 4;
+    /// Celdas que cubre el volumen celular antes de repetirse. La granulación (28 celdas
+    /// por radio) necesitaría 64 para no repetirse nunca, demasiada memoria con vóxeles
+    /// suficientes por celda. Con 32 la esfera y su copia desplazada un periodo solo
+    /// cortan el volumen igual sobre el círculo donde se intersecan, y allí lo cortan con
+    /// otra inclinación; además cada época del patrón se lee con otro desplazamiento y
+    /// otra deformación, así que no se ven baldosas
+    pub const CELL_PERIOD: u32 = 32;
+
+    /// Hornea ambos volúmenes con `resolution`³ vóxeles cada uno
+    pub fn bake(noise: &NoiseGenerator, resolution: u32) -> Self {
+        let period = Self::TURBULENCE_PERIOD;
+        let turbulence = NoiseVolume::bake(resolution, period as f32, |p| {
+            [noise.turbulence_periodic(p.x, p.y, p.z, Self::TURBULENCE_OCTAVES, [period; 3])]
+        });
+        let cells = NoiseVolume::bake(resolution, Self::CELL_PERIOD as f32, |p| {
+            let sample = noise.cellular_sample_periodic(p, DistanceMetric::Euclidean, 1.0, Self::CELL_PERIOD);
+            [sample.f1, sample.f2, sample.cell_value()]
+        });
+        Self { turbulence, cells }
+    }
+
+    /// `turbulence_slices` de 4 octavas y periodo `TURBULENCE_PERIOD` leída del volumen
+    /// (misma media que `turbulence`, ~0.94)
+    pub fn turbulence(&self, position: Vec3, w: f32) -> f32 {
+        time_slices(w, Self::TURBULENCE_PERIOD as f32)
+            .iter()
+            .map(|&(offset, weight)| weight * self.turbulence.sample(position + offset)[0])
+            .sum()
+    }
+
+    /// Ruido celular euclidiano con jitter 1, en unidades de celdas
+    /// F1 y F2 se interpolan; el valor de la celda se lee del vóxel más cercano para no
+    /// mezclar celdas vecinas. Los puntos característicos no se guardan (son None)
+    pub fn cells(&self, position: Vec3) -> CellularSample {
+        let [f1, f2, _] = self.cells.sample(position);
+        let [_, _, value] = self.cells.nearest(position);
+        CellularSample {
+            f1,
+            f2,
+            feature_point: None,
+            second_point: None,
+            cell_id: (value.clamp(0.0, 1.0) * u32::MAX as f32) as u32,
+        }
+    }
+
+    /// Gradientes de F1 y F2 en `position` (los de la interpolación trilineal), en lugar
+    /// de los analíticos de `CellularSample::gradients`, que necesitan los puntos
+    pub fn cell_gradients(&self, position: Vec3) -> (Vec3, Vec3) {
+        let [f1, f2, _] = self.cells.gradient(position);
+        (f1, f2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::test_points::points;
+
+    #[test]
+    fn voxels_hold_the_baked_field() {
+        let volume = NoiseVolume::bake(8, 4.0, |p| [p.x, p.y * 2.0]);
+        assert_eq!(volume.sample(Vec3::new(1.0, 0.5, 3.5)), [1.0, 1.0]);
+        // Entre dos vóxeles se interpola linealmente
+        let [x, _] = volume.sample(Vec3::new(1.25, 0.0, 0.0));
+        assert!((x - 1.25).abs() < 1e-5);
+        let [dx, dy] = volume.gradient(Vec3::new(1.3, 0.6, 2.1));
+        assert!((dx - Vec3::X).length() < 1e-4 && (dy - Vec3::Y * 2.0).length() < 1e-4);
+        // Sin interpolar se toma el vóxel más cercano
+        assert_eq!(volume.nearest(Vec3::new(1.2, 0.0, 0.0)), [1.0, 0.0]);
+    }
+
+    #[test]
+    fn volumes_tile() {
+        let noise = NoiseGenerator::new(5);
+        let baked = BakedNoise::bake(&noise, 16);
+        let period = BakedNoise::TURBULENCE_PERIOD as f32;
+        for p in points(300) {
+            let shifted = p + Vec3::new(period, -2.0 * period, 3.0 * period);
+            assert!((baked.turbulence(p, 2.6) - baked.turbulence(shifted, 2.6)).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn baked_noise_follows_the_exact_noise() {
+        let noise = NoiseGenerator::new(2);
+        let baked = BakedNoise::bake(&noise, 128);
+        let period = BakedNoise::TURBULENCE_PERIOD;
+        let (mut turbulence_error, mut cell_error) = (0.0, 0.0);
+        for (i, p) in points(300).enumerate() {
+            // El tiempo recorre varias rebanadas, también negativas
+            let w = i as f32 * 0.047 - 3.0;
+            let exact = noise.turbulence_slices(p, w, BakedNoise::TURBULENCE_OCTAVES, period);
+            turbulence_error += (baked.turbulence(p, w) - exact).abs();
+            let exact = noise.cellular_sample_periodic(p, DistanceMetric::Euclidean, 1.0, BakedNoise::CELL_PERIOD);
+            cell_error += (baked.cells(p).f1 - exact.f1).abs();
+        }
+        let n = points(300).count() as f32;
+        assert!(turbulence_error / n < 0.03, "turbulencia {}", turbulence_error / n);
+        assert!(cell_error / n < 0.03, "celdas {}", cell_error / n);
+    }
+}
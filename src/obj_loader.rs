@@ -1,12 +1,14 @@
 use raylib::prelude::*;
 use crate::vertex::Vertex;
+use crate::subdivision::{SubdivisionScheme, loop_subdivide, catmull_clark_subdivide, compute_normals, triangulate};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
 /// Estructura para almacenar la geometría cargada desde un archivo OBJ
 pub struct ObjModel {
     pub vertices: Vec<Vertex>,
-    pub indices: Vec<usize>,
+    pub indices: Vec<usize>,          // Triángulos (cada 3 índices forman uno)
+    pub faces: Vec<Vec<usize>>,       // Caras originales (triángulos, quads o polígonos)
 }
 
 impl ObjModel {
@@ -19,7 +21,7 @@ impl ObjModel {
         
         let mut positions = Vec::new();
         let mut normals = Vec::new();
-        let mut indices = Vec::new();
+        let mut faces = Vec::new();
         
         // Leer el archivo línea por línea
         for line in reader.lines() {
@@ -61,20 +63,21 @@ impl ObjModel {
                         normals.push(Vector3::new(x, y, z));
                     }
                 }
-                // Cara: f v1/vt1/vn1 v2/vt2/vn2 v3/vt3/vn3
+                // Cara: f v1/vt1/vn1 v2/vt2/vn2 v3/vt3/vn3 [v4/vt4/vn4 ...]
                 "f" => {
                     if parts.len() >= 4 {
-                        for i in 1..=3 {
-                            let face_part = parts[i];
+                        let mut face = Vec::with_capacity(parts.len() - 1);
+                        for face_part in &parts[1..] {
                             let values: Vec<&str> = face_part.split('/').collect();
                             
                             if !values.is_empty() {
                                 // Los índices en OBJ empiezan en 1, no en 0
                                 let vertex_index = values[0].parse::<usize>()
                                     .map_err(|e| format!("Error parseando índice: {}", e))? - 1;
-                                indices.push(vertex_index);
+                                face.push(vertex_index);
                             }
                         }
+                        faces.push(face);
                     }
                 }
                 _ => {} // Ignorar otras líneas (vt, etc.)
@@ -108,9 +111,44 @@ impl ObjModel {
             vertices.push(Vertex::new(*pos, normal));
         }
         
+        // Triangular las caras (los quads y polígonos se dividen en abanico)
+        let indices = triangulate(&faces);
+        
         println!("✅ OBJ cargado: {} vértices, {} índices", vertices.len(), indices.len());
         
-        Ok(ObjModel { vertices, indices })
+        Ok(ObjModel { vertices, indices, faces })
+    }
+    
+    /// Subdivide la malla `levels` veces con el esquema indicado y recalcula las normales
+    /// Loop trabaja sobre los triángulos; Catmull-Clark sobre las caras originales
+    /// (quads) y el resultado se triangula al final
+    pub fn subdivide(&mut self, scheme: SubdivisionScheme, levels: u32) {
+        if levels == 0 {
+            return;
+        }
+        
+        match scheme {
+            SubdivisionScheme::Loop => {
+                for _ in 0..levels {
+                    let (vertices, indices) = loop_subdivide(&self.vertices, &self.indices);
+                    self.vertices = vertices;
+                    self.indices = indices;
+                }
+                self.faces = self.indices.chunks(3).map(|tri| tri.to_vec()).collect();
+            }
+            SubdivisionScheme::CatmullClark => {
+                for _ in 0..levels {
+                    let (vertices, faces) = catmull_clark_subdivide(&self.vertices, &self.faces);
+                    self.vertices = vertices;
+                    self.faces = faces;
+                }
+                self.indices = triangulate(&self.faces);
+            }
+        }
+        
+        compute_normals(&mut self.vertices, &self.indices);
+        
+        println!("✅ Malla subdividida: {} vértices, {} índices", self.vertices.len(), self.indices.len());
     }
     
     /// Calcula normales si el archivo OBJ no las tiene
@@ -0,0 +1,320 @@
+//! PARTÍCULAS
+//! Sistema de partículas en CPU para viento solar, fulguraciones y eyecciones de masa
+//! coronal. Los emisores se fijan a una latitud y longitud de la estrella (y giran con
+//! la rotación diferencial de la superficie); cada partícula nace con un impulso hacia
+//! afuera, la arrastra un campo de velocidad de ruido curl y la frena la gravedad.
+//! Se dibujan como sprites circulares con mezcla aditiva y prueba de profundidad
+//!
+//! Las posiciones se guardan en el espacio de la estrella en radios estelares
+//! (radio 1 = superficie), así el sistema no depende de la escala del modelo
+
+use raylib::prelude::*;
+use crate::framebuffer::Framebuffer;
+use crate::uniforms::Uniforms;
+use crate::math::Vec3;
+use crate::noise::{default_generator, CurlField};
+use crate::random::Rng;
+use crate::blackbody::linear_to_srgb;
+use crate::differential_rotation::DifferentialRotation;
+use crate::sunspots::direction;
+
+/// Límite de partículas vivas (las que sobran no se emiten)
+const MAX_PARTICLES: usize = 4000;
+
+/// Fuente de partículas fija a un punto de la superficie
+#[derive(Clone, Copy, Debug)]
+pub struct Emitter {
+    pub latitude: f32,          // Latitud del punto de emisión (radianes)
+    pub longitude: f32,         // Longitud al tiempo 0 (radianes)
+    pub rate: f32,              // Partículas por segundo
+    pub duration: Option<f32>,  // Segundos que dura la emisión (None = continua)
+    pub lifetime: (f32, f32),   // Vida mínima y máxima de cada partícula (segundos)
+    pub speed: (f32, f32),      // Rapidez inicial mínima y máxima (radios por segundo)
+    pub spread: f32,            // Apertura del cono de emisión alrededor de la normal (radianes)
+    pub size: (f32, f32),       // Tamaño al nacer y al morir (radios estelares)
+    pub color: (Vec3, Vec3),    // Color al nacer y al morir (RGB lineal, ya multiplicado por el brillo)
+    age: f32,
+    pending: f32,               // Fracción de partícula acumulada entre cuadros
+}
+
+impl Emitter {
+    /// Fulguración: chorro corto, rápido y muy brillante que vuelve a caer
+    pub fn flare(latitude: f32, longitude: f32) -> Self {
+        Self {
+            latitude,
+            longitude,
+            rate: 400.0,
+            duration: Some(1.5),
+            lifetime: (1.5, 3.5),
+            speed: (0.6, 1.4),
+            spread: 0.35,
+            size: (0.025, 0.008),
+            color: (Vec3::new(1.6, 1.3, 0.9), Vec3::new(0.6, 0.08, 0.04)),
+            age: 0.0,
+            pending: 0.0,
+        }
+    }
+
+    /// Eyección de masa coronal: nube ancha y lenta que escapa de la estrella
+    pub fn coronal_mass_ejection(latitude: f32, longitude: f32) -> Self {
+        Self {
+            latitude,
+            longitude,
+            rate: 250.0,
+            duration: Some(4.0),
+            lifetime: (5.0, 9.0),
+            speed: (0.5, 0.9),
+            spread: 0.7,
+            size: (0.02, 0.05),
+            color: (Vec3::new(0.45, 0.2, 0.16), Vec3::new(0.06, 0.02, 0.04)),
+            age: 0.0,
+            pending: 0.0,
+        }
+    }
+
+    /// Viento solar: flujo continuo, tenue y disperso
+    pub fn solar_wind(latitude: f32, longitude: f32) -> Self {
+        Self {
+            latitude,
+            longitude,
+            rate: 30.0,
+            duration: None,
+            lifetime: (6.0, 10.0),
+            speed: (0.5, 0.8),
+            spread: 1.2,
+            size: (0.012, 0.02),
+            color: (Vec3::new(0.25, 0.22, 0.2), Vec3::new(0.0, 0.0, 0.0)),
+            age: 0.0,
+            pending: 0.0,
+        }
+    }
+
+    /// true cuando terminó de emitir
+    pub fn finished(&self) -> bool {
+        self.duration.is_some_and(|duration| self.age >= duration)
+    }
+
+    /// Latitud y longitud (al tiempo 0) del punto de la superficie que en `time` está en
+    /// `position`: para fijar un emisor sobre algo que ya gira, como una mancha
+    pub fn surface_coordinates(position: Vec3, time: f32, rotation: &DifferentialRotation) -> (f32, f32) {
+        let position = position.normalize();
+        let latitude = position.y.clamp(-1.0, 1.0).asin();
+        let longitude = position.x.atan2(position.z) - rotation.angle(latitude, time);
+        (latitude, longitude)
+    }
+
+    /// Punto de emisión sobre la esfera unitaria, girado con la superficie
+    pub fn surface_position(&self, time: f32, rotation: &DifferentialRotation) -> Vec3 {
+        direction(self.latitude, self.longitude + rotation.angle(self.latitude, time))
+    }
+}
+
+/// Partícula viva
+#[derive(Clone, Copy, Debug)]
+pub struct Particle {
+    pub position: Vec3,   // Espacio de la estrella (radios estelares)
+    pub velocity: Vec3,   // Velocidad propia (sin contar el campo de ruido)
+    pub age: f32,
+    pub lifetime: f32,
+    pub size: (f32, f32),
+    pub color: (Vec3, Vec3),
+}
+
+impl Particle {
+    /// Fracción de vida transcurrida en [0, 1]
+    pub fn life(&self) -> f32 {
+        (self.age / self.lifetime).clamp(0.0, 1.0)
+    }
+
+    pub fn current_size(&self) -> f32 {
+        self.size.0 + (self.size.1 - self.size.0) * self.life()
+    }
+
+    /// Color actual: interpola nacimiento -> muerte y se desvanece al nacer y al morir
+    pub fn current_color(&self) -> Vec3 {
+        let life = self.life();
+        let fade = (life / 0.1).min(1.0) * (1.0 - life);
+        self.color.0.lerp(self.color.1, life) * fade
+    }
+}
+
+/// Conjunto de emisores y partículas
+pub struct ParticleSystem {
+    pub emitters: Vec<Emitter>,
+    pub particles: Vec<Particle>,
+    pub gravity: f32,        // Aceleración hacia la estrella en la superficie (cae con 1/r²)
+    pub drag: f32,           // Frenado de la velocidad propia por segundo
+    pub flow: CurlField,     // Campo de ruido curl que arrastra las partículas (radios por segundo)
+    rng: Rng,
+}
+
+impl ParticleSystem {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            emitters: Vec::new(),
+            particles: Vec::new(),
+            gravity: 0.5,
+            drag: 0.2,
+            flow: CurlField::new(2.0, 0.15, 0.1),
+            rng: Rng::new(seed),
+        }
+    }
+
+    pub fn add_emitter(&mut self, emitter: Emitter) {
+        self.emitters.push(emitter);
+    }
+
+    /// Dirección aleatoria dentro de un cono de apertura `spread` alrededor de `axis`
+    fn cone_direction(&mut self, axis: Vec3, spread: f32) -> Vec3 {
+        let reference = if axis.y.abs() > 0.99 { Vec3::X } else { Vec3::Y };
+        let tangent = reference.cross(axis).normalize();
+        let bitangent = axis.cross(tangent);
+        // Coseno uniforme en [cos(spread), 1] reparte las direcciones uniformemente en el cono
+        let cos_theta = 1.0 - self.rng.next_f32() * (1.0 - spread.cos());
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = self.rng.range(0.0, std::f32::consts::TAU);
+        (tangent * phi.cos() + bitangent * phi.sin()) * sin_theta + axis * cos_theta
+    }
+
+    /// Avanza la simulación `dt` segundos; `time` es el tiempo ya actualizado
+    pub fn update(&mut self, dt: f32, time: f32, rotation: &DifferentialRotation) {
+        // ==========================================
+        // 1. EMISIÓN
+        // ==========================================
+        let mut emitters = std::mem::take(&mut self.emitters);
+        for emitter in &mut emitters {
+            let active = emitter.duration.map_or(dt, |duration| (duration - emitter.age).clamp(0.0, dt));
+            emitter.age += dt;
+            emitter.pending += emitter.rate * active;
+
+            let normal = emitter.surface_position(time, rotation);
+            while emitter.pending >= 1.0 {
+                emitter.pending -= 1.0;
+                if self.particles.len() >= MAX_PARTICLES {
+                    continue;
+                }
+                let direction = self.cone_direction(normal, emitter.spread);
+                let speed = self.rng.range(emitter.speed.0, emitter.speed.1);
+                let lifetime = self.rng.range(emitter.lifetime.0, emitter.lifetime.1);
+                self.particles.push(Particle {
+                    position: normal * 1.01,
+                    velocity: direction * speed,
+                    age: 0.0,
+                    lifetime,
+                    size: emitter.size,
+                    color: emitter.color,
+                });
+            }
+        }
+        emitters.retain(|emitter| !emitter.finished());
+        self.emitters = emitters;
+
+        // ==========================================
+        // 2. INTEGRACIÓN (Euler semi-implícito)
+        // ==========================================
+        for particle in &mut self.particles {
+            let r = particle.position.length().max(1.0);
+            let gravity = -particle.position / r * (self.gravity / (r * r));
+            particle.velocity = (particle.velocity + gravity * dt) * (1.0 - self.drag * dt).max(0.0);
+
+            let field = self.flow.velocity(default_generator(), particle.position, time);
+            particle.position += (particle.velocity + field) * dt;
+            particle.age += dt;
+        }
+
+        // ==========================================
+        // 3. MUERTE: fin de la vida o caída de vuelta a la superficie
+        // ==========================================
+        self.particles.retain(|particle| particle.age < particle.lifetime && particle.position.length() > 1.0);
+    }
+
+    /// Dibuja las partículas de la estrella descrita por `uniforms.model_matrix`
+    /// `mesh_radius` es el radio del modelo en espacio objeto
+    pub fn render(&self, framebuffer: &mut Framebuffer, uniforms: &Uniforms, mesh_radius: f32) {
+        let model = uniforms.model_matrix;
+        let camera_right = uniforms.view_matrix.row(0).truncate().normalize();
+        let world_per_radius = mesh_radius * model.column(0).truncate().length();
+
+        for particle in &self.particles {
+            let world = model.transform_point(particle.position * mesh_radius);
+            let Some(screen) = uniforms.world_to_screen(world) else {
+                continue;
+            };
+            let color = particle.current_color();
+            if color.length_squared() < 1e-6 {
+                continue;
+            }
+
+            // Radio del sprite en píxeles: se proyecta un punto desplazado hacia la derecha
+            let size = particle.current_size() * world_per_radius;
+            let Some(edge) = uniforms.world_to_screen(world + camera_right * size) else {
+                continue;
+            };
+            let radius = Vector2::new(edge.x - screen.x, edge.y - screen.y).length().clamp(0.75, 24.0);
+
+            // Los sprites de menos de dos píxeles de radio se oscurecen para no parpadear
+            let energy = (radius / 2.0).clamp(0.35, 1.0);
+
+            let min_x = (screen.x - radius).floor() as i32;
+            let max_x = (screen.x + radius).ceil() as i32;
+            let min_y = (screen.y - radius).floor() as i32;
+            let max_y = (screen.y + radius).ceil() as i32;
+            for y in min_y..=max_y {
+                for x in min_x..=max_x {
+                    let dx = x as f32 + 0.5 - screen.x;
+                    let dy = y as f32 + 0.5 - screen.y;
+                    let d2 = (dx * dx + dy * dy) / (radius * radius);
+                    if d2 >= 1.0 || !framebuffer.depth_passes(x, y, screen.z) {
+                        continue;
+                    }
+
+                    // Sprite suave: cae a cero en el borde
+                    let falloff = (1.0 - d2) * (1.0 - d2) * energy;
+                    framebuffer.blend_add(x, y, Color::new(
+                        (linear_to_srgb(color.x * falloff) * 255.0) as u8,
+                        (linear_to_srgb(color.y * falloff) * 255.0) as u8,
+                        (linear_to_srgb(color.z * falloff) * 255.0) as u8,
+                        255,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emitters_spawn_at_their_rate_and_stop() {
+        let rotation = DifferentialRotation::default();
+        let mut system = ParticleSystem::new(3);
+        system.add_emitter(Emitter { duration: Some(1.0), lifetime: (10.0, 10.0), ..Emitter::flare(0.3, 1.0) });
+        for frame in 0..60 {
+            system.update(1.0 / 30.0, frame as f32 / 30.0, &rotation);
+        }
+        // 1 segundo a 400 partículas por segundo (alguna puede haber caído a la superficie)
+        assert!((380..=400).contains(&system.particles.len()), "{}", system.particles.len());
+        assert!(system.emitters.is_empty());
+    }
+
+    #[test]
+    fn particles_start_at_the_emitter_and_die() {
+        let rotation = DifferentialRotation::default();
+        let mut system = ParticleSystem::new(5);
+        let emitter = Emitter::solar_wind(0.4, -0.7);
+        let origin = emitter.surface_position(0.0, &rotation);
+        system.add_emitter(Emitter { duration: Some(0.1), ..emitter });
+        system.update(0.1, 0.0, &rotation);
+        assert!(!system.particles.is_empty());
+        for particle in &system.particles {
+            assert!((particle.position.normalize() - origin).length() < 0.2);
+        }
+
+        for frame in 0..400 {
+            system.update(0.05, frame as f32 * 0.05, &rotation);
+        }
+        assert!(system.particles.is_empty());
+    }
+}
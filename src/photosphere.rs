@@ -0,0 +1,457 @@
+//! FOTOSFERA
+//! Modelo por capas de la superficie visible de la estrella:
+//! - Granulación: celdas de convección pequeñas, centros brillantes (gas caliente que sube)
+//!   separados por carriles oscuros (gas frío que baja); nacen y mueren en minutos
+//! - Supergranulación: celdas ~30 veces más grandes que modulan el brillo suavemente
+//! - Fáculas: regiones brillantes en la red de supergránulos, visibles sobre todo cerca del limbo
+
+use crate::math::{smoothstep, smoothstep_derivative, Vec3};
+use crate::noise::{BakedNoise, CellularBatch, CellularSample, DistanceMetric, F32x4, NoiseGenerator, Simd, TimeLoop, Vec3Batch};
+use crate::uniforms::Uniforms;
+use crate::differential_rotation::DifferentialRotation;
+
+/// Parámetros de una capa de la fotosfera
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SurfaceLayer {
+    pub scale: f32,    // Celdas por unidad de radio
+    pub speed: f32,    // Renovaciones del patrón por segundo
+    pub contrast: f32, // Peso de la capa en la intensidad final
+    pub repeat: u32,   // Renovaciones tras las que el patrón se repite (0 = nunca)
+}
+
+impl SurfaceLayer {
+    pub const fn new(scale: f32, speed: f32, contrast: f32) -> Self {
+        Self { scale, speed, contrast, repeat: 0 }
+    }
+
+    /// La misma capa ajustada a un bucle: un número entero de renovaciones por bucle,
+    /// y el patrón vuelve a la primera época al terminarlo
+    pub fn looped(self, time_loop: &TimeLoop) -> Self {
+        let cycles = time_loop.cycles(self.speed);
+        Self { speed: cycles as f32 / time_loop.duration, repeat: cycles, ..self }
+    }
+
+    pub const GRANULATION: SurfaceLayer = SurfaceLayer::new(28.0, 0.15, 0.22);
+    pub const SUPERGRANULATION: SurfaceLayer = SurfaceLayer::new(4.0, 0.02, 0.08);
+    pub const FACULAE: SurfaceLayer = SurfaceLayer::new(6.0, 0.05, 0.35);
+}
+
+/// Desplazamiento acotado y distinto para cada época del patrón
+fn epoch_offset(epoch: f32) -> Vec3 {
+    let fract = |x: f32| x - x.floor();
+    Vec3::new(fract(epoch * 0.618_034), fract(epoch * 0.414_214), fract(epoch * 0.732_051)) * 64.0
+}
+
+/// Instante en que aparece una época del patrón
+fn epoch_born(epoch: f32, layer: &SurfaceLayer) -> f32 {
+    if layer.speed > 0.0 { (epoch - 1.0) / layer.speed } else { 0.0 }
+}
+
+/// Punto donde se muestrea una época del patrón: cada época gira con la superficie
+/// desde que aparece, de modo que la rotación diferencial la cizalla solo durante su vida
+fn epoch_point(position: Vec3, time: f32, epoch: f32, layer: &SurfaceLayer, rotation: &DifferentialRotation) -> Vec3 {
+    let frame = rotation.surface_frame_since(position, time, epoch_born(epoch, layer));
+    let pattern = if layer.repeat > 0 { epoch.rem_euclid(layer.repeat as f32) } else { epoch };
+    frame + epoch_offset(pattern)
+}
+
+/// Evalúa un patrón que se renueva con el tiempo: se mezclan suavemente dos "épocas"
+/// del campo desplazadas entre sí, así unos rasgos se disuelven mientras otros aparecen
+fn evolving<F: Fn(Vec3, f32) -> f32>(
+    position: Vec3,
+    time: f32,
+    layer: &SurfaceLayer,
+    rotation: &DifferentialRotation,
+    field: F,
+) -> f32 {
+    let t = time * layer.speed;
+    let epoch = t.floor();
+    let blend = smoothstep(0.0, 1.0, t - epoch);
+
+    // El campo recibe la edad de la época (en renovaciones) y no `t`, así una época
+    // vuelve a ser idéntica cuando el patrón se repite
+    let sample = |epoch: f32| field(epoch_point(position, time, epoch, layer, rotation), t - epoch + 1.0);
+
+    let current = sample(epoch);
+    let next = sample(epoch + 1.0);
+    current + (next - current) * blend
+}
+
+/// `evolving` para 4 puntos a la vez: cada época es un lote SIMD
+fn evolving_batch<F: Fn(&Vec3Batch<F32x4>, F32x4) -> F32x4>(
+    positions: &[Vec3; 4],
+    time: f32,
+    layer: &SurfaceLayer,
+    rotation: &DifferentialRotation,
+    field: F,
+) -> [f32; 4] {
+    let t = time * layer.speed;
+    let epoch = t.floor();
+    let blend = smoothstep(0.0, 1.0, t - epoch);
+
+    let sample = |epoch: f32| {
+        let points = Vec3Batch::from_fn(|i| epoch_point(positions[i], time, epoch, layer, rotation));
+        field(&points, F32x4::splat(t - epoch + 1.0))
+    };
+
+    let current = sample(epoch);
+    let next = sample(epoch + 1.0);
+    (current + (next - current) * F32x4::splat(blend)).to_array()
+}
+
+/// `evolving` para un campo con gradiente (respecto al punto de muestreo): el gradiente
+/// de cada época se devuelve a espacio objeto desde su marco antes de mezclarlas
+fn evolving_gradient<F: Fn(Vec3, f32) -> (f32, Vec3)>(
+    position: Vec3,
+    time: f32,
+    layer: &SurfaceLayer,
+    rotation: &DifferentialRotation,
+    field: F,
+) -> (f32, Vec3) {
+    let t = time * layer.speed;
+    let epoch = t.floor();
+    let blend = smoothstep(0.0, 1.0, t - epoch);
+
+    let sample = |epoch: f32| {
+        let (value, gradient) = field(epoch_point(position, time, epoch, layer, rotation), t - epoch + 1.0);
+        (value, rotation.surface_frame_since_gradient(position, gradient, time, epoch_born(epoch, layer)))
+    };
+
+    let (current, current_gradient) = sample(epoch);
+    let (next, next_gradient) = sample(epoch + 1.0);
+    (current + (next - current) * blend, current_gradient + (next_gradient - current_gradient) * blend)
+}
+
+/// `evolving_gradient` para 4 puntos a la vez: `field` da los valores del lote y el
+/// gradiente de cada carril
+fn evolving_gradient_batch<F: Fn(&Vec3Batch<F32x4>, F32x4) -> [(f32, Vec3); 4]>(
+    positions: &[Vec3; 4],
+    time: f32,
+    layer: &SurfaceLayer,
+    rotation: &DifferentialRotation,
+    field: F,
+) -> [(f32, Vec3); 4] {
+    let t = time * layer.speed;
+    let epoch = t.floor();
+    let blend = smoothstep(0.0, 1.0, t - epoch);
+
+    let sample = |epoch: f32| {
+        let points = Vec3Batch::from_fn(|i| epoch_point(positions[i], time, epoch, layer, rotation));
+        let born = epoch_born(epoch, layer);
+        let mut values = field(&points, F32x4::splat(t - epoch + 1.0));
+        for ((_, gradient), &position) in values.iter_mut().zip(positions) {
+            *gradient = rotation.surface_frame_since_gradient(position, *gradient, time, born);
+        }
+        values
+    };
+
+    let current = sample(epoch);
+    let next = sample(epoch + 1.0);
+    std::array::from_fn(|i| {
+        let ((value, gradient), (next_value, next_gradient)) = (current[i], next[i]);
+        (value + (next_value - value) * blend, gradient + (next_gradient - gradient) * blend)
+    })
+}
+
+/// Punto (en celdas) donde se lee el patrón celular, con una pequeña deformación para
+/// que las celdas no sean polígonos perfectos
+fn warped_point(noise: &NoiseGenerator, p: Vec3, t: f32, layer: &SurfaceLayer) -> Vec3 {
+    let warp = Vec3::new(
+        noise.perlin(p.x * 3.0, p.y * 3.0, p.z * 3.0 + t),
+        noise.perlin(p.y * 3.0 + 7.1, p.z * 3.0, p.x * 3.0 + t),
+        noise.perlin(p.z * 3.0, p.x * 3.0 + 3.7, p.y * 3.0 + t),
+    ) - Vec3::splat(0.5);
+    (p + warp * (0.6 / layer.scale)) * layer.scale
+}
+
+/// Lleva un gradiente respecto al punto en celdas de vuelta a `p` (regla de la cadena a
+/// través de `warped_point`): la escala más la jacobiana de la deformación, cuyas tres
+/// componentes salen de las derivadas analíticas de Perlin con los ejes de cada lectura
+fn warp_pullback(noise: &NoiseGenerator, p: Vec3, t: f32, layer: &SurfaceLayer, gradient: Vec3) -> Vec3 {
+    let (_, dx) = noise.perlin_derivative(p.x * 3.0, p.y * 3.0, p.z * 3.0 + t);
+    let (_, dy) = noise.perlin_derivative(p.y * 3.0 + 7.1, p.z * 3.0, p.x * 3.0 + t);
+    let (_, dz) = noise.perlin_derivative(p.z * 3.0, p.x * 3.0 + 3.7, p.y * 3.0 + t);
+    let (dy, dz) = (Vec3::new(dy.z, dy.x, dy.y), Vec3::new(dz.y, dz.z, dz.x));
+    gradient * layer.scale + (dx * gradient.x + dy * gradient.y + dz * gradient.z) * (3.0 * 0.6)
+}
+
+/// `warped_point` para un lote de puntos
+fn warped_point_batch<S: Simd>(noise: &NoiseGenerator, p: &Vec3Batch<S>, t: S, layer: &SurfaceLayer) -> Vec3Batch<S> {
+    let (x, y, z) = (p.x * S::splat(3.0), p.y * S::splat(3.0), p.z * S::splat(3.0));
+    let half = S::splat(0.5);
+    let warp = Vec3Batch::new(
+        noise.perlin_batch(&Vec3Batch::new(x, y, z + t)) - half,
+        noise.perlin_batch(&Vec3Batch::new(y + S::splat(7.1), z, x + t)) - half,
+        noise.perlin_batch(&Vec3Batch::new(z, x + S::splat(3.7), y + t)) - half,
+    );
+    (*p + warp.scale(S::splat(0.6 / layer.scale))).scale(S::splat(layer.scale))
+}
+
+/// Patrón celular en un punto deformado (en celdas)
+/// Con `baked` la búsqueda de 27 celdas se cambia por una lectura del volumen horneado
+fn cells_at(noise: &NoiseGenerator, baked: Option<&BakedNoise>, q: Vec3) -> CellularSample {
+    match baked {
+        Some(baked) => baked.cells(q),
+        None => noise.cellular_sample_periodic(q, DistanceMetric::Euclidean, 1.0, BakedNoise::CELL_PERIOD),
+    }
+}
+
+/// `cells_at` para un lote de puntos
+fn cells_at_batch<S: Simd>(noise: &NoiseGenerator, baked: Option<&BakedNoise>, q: &Vec3Batch<S>) -> CellularBatch<S> {
+    match baked {
+        Some(baked) => CellularBatch::from_fn(|lane| baked.cells(q.lane(lane))),
+        None => noise.cellular_periodic_batch(q, DistanceMetric::Euclidean, 1.0, BakedNoise::CELL_PERIOD),
+    }
+}
+
+/// Gradientes de F1 y F2 en `q` (en celdas): analíticos con el ruido exacto, los de la
+/// interpolación trilineal con el horneado
+fn cell_gradients(baked: Option<&BakedNoise>, cells: &CellularSample, q: Vec3) -> (Vec3, Vec3) {
+    match cells.gradients(q) {
+        Some(gradients) => gradients,
+        None => baked.map_or((Vec3::ZERO, Vec3::ZERO), |baked| baked.cell_gradients(q)),
+    }
+}
+
+/// Brillo de un punto de la granulación a partir de sus celdas, y su gradiente a partir
+/// de los de F1 y F2 (regla de la cadena sobre la misma fórmula)
+fn granule(cells: &CellularSample, (d_f1, d_f2): (Vec3, Vec3)) -> (f32, Vec3) {
+    let lanes = smoothstep(0.0, 0.35, cells.edge());
+    let d_lanes = (d_f2 - d_f1) * smoothstep_derivative(0.0, 0.35, cells.edge());
+    let base = 0.75 + 0.25 * cells.cell_value();
+    let brightness = base * (1.0 - 0.25 * cells.f1.min(1.0));
+    let d_brightness = if cells.f1 < 1.0 { d_f1 * (-0.25 * base) } else { Vec3::ZERO };
+    (2.0 * lanes * brightness - 1.0, (d_lanes * brightness + d_brightness * lanes) * 2.0)
+}
+
+/// Granulación en [-1, 1]: +1 en el centro de un gránulo, -1 en los carriles oscuros
+/// Los carriles salen de F2 - F1 (grosor uniforme entre gránulos) y cada gránulo
+/// tiene su propio brillo según el identificador de su celda
+/// También retorna el gradiente tangente del mismo campo en espacio objeto: tomada como
+/// altura, la granulación da el relieve (gránulos abombados, carriles hundidos) y restar
+/// el gradiente de la normal da el bump mapping. Sale de las derivadas analíticas de F1
+/// y F2, sin diferencias finitas
+pub fn granulation(noise: &NoiseGenerator, baked: Option<&BakedNoise>, position: Vec3, time: f32, layer: &SurfaceLayer, rotation: &DifferentialRotation) -> (f32, Vec3) {
+    let (value, gradient) = evolving_gradient(position, time, layer, rotation, |p, t| {
+        let q = warped_point(noise, p, t, layer);
+        let cells = cells_at(noise, baked, q);
+        let (value, gradient) = granule(&cells, cell_gradients(baked, &cells, q));
+        (value, warp_pullback(noise, p, t, layer, gradient))
+    });
+    (value, tangent(gradient, position))
+}
+
+/// `granulation` para 4 puntos a la vez
+pub fn granulation_batch(noise: &NoiseGenerator, baked: Option<&BakedNoise>, positions: &[Vec3; 4], time: f32, layer: &SurfaceLayer, rotation: &DifferentialRotation) -> [(f32, Vec3); 4] {
+    let granules = evolving_gradient_batch(positions, time, layer, rotation, |p, t| {
+        let q = warped_point_batch(noise, p, t, layer);
+        let cells = cells_at_batch(noise, baked, &q);
+        std::array::from_fn(|lane| {
+            let (sample, q) = (cells.sample(lane), q.lane(lane));
+            let (value, gradient) = granule(&sample, cell_gradients(baked, &sample, q));
+            (value, warp_pullback(noise, p.lane(lane), t.lane(lane), layer, gradient))
+        })
+    });
+    std::array::from_fn(|i| (granules[i].0, tangent(granules[i].1, positions[i])))
+}
+
+/// Componente de `gradient` tangente a la esfera en `position`: la radial no inclina la normal
+fn tangent(gradient: Vec3, position: Vec3) -> Vec3 {
+    gradient - position * gradient.dot(position)
+}
+
+/// Modulación y red de la supergranulación a partir de F1
+fn supergranule(distance: f32) -> (f32, f32) {
+    let modulation = 1.0 - 2.0 * smoothstep(0.0, 1.0, distance);
+    let network = smoothstep(0.55, 0.95, distance);
+    (modulation, network)
+}
+
+/// Supergranulación en [-1, 1] y la red de bordes entre supergránulos en [0, 1]
+pub fn supergranulation(noise: &NoiseGenerator, baked: Option<&BakedNoise>, position: Vec3, time: f32, layer: &SurfaceLayer, rotation: &DifferentialRotation) -> (f32, f32) {
+    supergranule(evolving(position, time, layer, rotation, |p, t| cells_at(noise, baked, warped_point(noise, p, t, layer)).f1))
+}
+
+/// `supergranulation` para 4 puntos a la vez
+pub fn supergranulation_batch(noise: &NoiseGenerator, baked: Option<&BakedNoise>, positions: &[Vec3; 4], time: f32, layer: &SurfaceLayer, rotation: &DifferentialRotation) -> [(f32, f32); 4] {
+    let distances = evolving_batch(positions, time, layer, rotation, |p, t| cells_at_batch(noise, baked, &warped_point_batch(noise, p, t, layer)).f1);
+    distances.map(supergranule)
+}
+
+/// Fáculas en [0, 1]: manchas brillantes sobre la red, con contraste creciente hacia el limbo
+pub fn faculae(
+    noise: &NoiseGenerator,
+    position: Vec3,
+    network: f32,
+    mu: f32,
+    time: f32,
+    layer: &SurfaceLayer,
+    rotation: &DifferentialRotation,
+) -> f32 {
+    let patches = evolving(position, time, layer, rotation, |p, _| {
+        let p = p * layer.scale;
+        smoothstep(0.55, 0.75, noise.perlin(p.x, p.y, p.z))
+    });
+    let limb = (1.0 - mu.clamp(0.0, 1.0)).powi(2);
+    network * patches * limb
+}
+
+/// `faculae` para 4 puntos a la vez
+pub fn faculae_batch(
+    noise: &NoiseGenerator,
+    positions: &[Vec3; 4],
+    network: [f32; 4],
+    mu: [f32; 4],
+    time: f32,
+    layer: &SurfaceLayer,
+    rotation: &DifferentialRotation,
+) -> [f32; 4] {
+    let patches = evolving_batch(positions, time, layer, rotation, |p, _| {
+        let values = noise.perlin_batch(&p.scale(F32x4::splat(layer.scale)));
+        F32x4::from_fn(|lane| smoothstep(0.55, 0.75, values.lane(lane)))
+    });
+    std::array::from_fn(|i| {
+        let limb = (1.0 - mu[i].clamp(0.0, 1.0)).powi(2);
+        network[i] * patches[i] * limb
+    })
+}
+
+/// Variación de intensidad de la fotosfera en un punto de la esfera unitaria
+/// (espacio objeto: la rotación de la superficie se aplica aquí, capa por capa)
+/// `granules` es la granulación del punto (el shader la calcula antes, para el relieve)
+/// y `mu` el coseno entre la normal y la dirección de vista
+pub fn photosphere_intensity(noise: &NoiseGenerator, baked: Option<&BakedNoise>, position: Vec3, granules: f32, mu: f32, uniforms: &Uniforms) -> f32 {
+    let time = uniforms.time;
+    let rotation = &uniforms.differential_rotation;
+    let (supergranules, network) = supergranulation(noise, baked, position, time, &uniforms.supergranulation, rotation);
+    let bright_faculae = faculae(noise, position, network, mu, time, &uniforms.faculae, rotation);
+
+    granules * uniforms.granulation.contrast
+        + supergranules * uniforms.supergranulation.contrast
+        + bright_faculae * uniforms.faculae.contrast
+}
+
+/// `photosphere_intensity` para 4 puntos a la vez, con el ruido de las tres capas
+/// evaluado en lotes
+pub fn photosphere_intensity_batch(noise: &NoiseGenerator, baked: Option<&BakedNoise>, positions: &[Vec3; 4], granules: [f32; 4], mu: [f32; 4], uniforms: &Uniforms) -> [f32; 4] {
+    let time = uniforms.time;
+    let rotation = &uniforms.differential_rotation;
+    let supergranules = supergranulation_batch(noise, baked, positions, time, &uniforms.supergranulation, rotation);
+    let network = supergranules.map(|(_, network)| network);
+    let bright_faculae = faculae_batch(noise, positions, network, mu, time, &uniforms.faculae, rotation);
+
+    std::array::from_fn(|i| {
+        granules[i] * uniforms.granulation.contrast
+            + supergranules[i].0 * uniforms.supergranulation.contrast
+            + bright_faculae[i] * uniforms.faculae.contrast
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::test_points::points;
+
+    /// Los puntos de prueba del ruido llevados a la esfera unidad
+    fn sample_points() -> impl Iterator<Item = Vec3> {
+        points(200).map(|p| p.normalize())
+    }
+
+    #[test]
+    fn layers_stay_in_range() {
+        for position in sample_points() {
+            let rotation = DifferentialRotation::default();
+            let noise = NoiseGenerator::default();
+            let (g, _) = granulation(&noise, None, position, 12.5, &SurfaceLayer::GRANULATION, &rotation);
+            let (s, network) = supergranulation(&noise, None, position, 12.5, &SurfaceLayer::SUPERGRANULATION, &rotation);
+            assert!((-1.0..=1.0).contains(&g));
+            assert!((-1.0..=1.0).contains(&s));
+            assert!((0.0..=1.0).contains(&network));
+        }
+    }
+
+    #[test]
+    fn granulation_has_both_granules_and_lanes() {
+        let noise = NoiseGenerator::default();
+        let values: Vec<f32> = sample_points()
+            .map(|p| granulation(&noise, None, p, 0.0, &SurfaceLayer::GRANULATION, &DifferentialRotation::default()).0)
+            .collect();
+        assert!(values.iter().any(|&v| v > 0.5));
+        assert!(values.iter().any(|&v| v < -0.5));
+    }
+
+    #[test]
+    fn relief_is_the_gradient_of_the_granulation() {
+        let noise = NoiseGenerator::new(3);
+        let layer = SurfaceLayer::GRANULATION;
+        let rotation = DifferentialRotation::default();
+        // A mitad de una mezcla de épocas, con el patrón ya girado
+        let time = 3.3;
+        let height = |p: Vec3| granulation(&noise, None, p, time, &layer, &rotation).0;
+        let h = 3e-4;
+        let (mut compared, mut matching) = (0, 0);
+        for position in sample_points() {
+            let (_, gradient) = granulation(&noise, None, position, time, &layer, &rotation);
+            assert!(gradient.dot(position).abs() < 1e-3);
+
+            // Derivada direccional a lo largo de una tangente; se saltan los puntos donde
+            // la celda ganadora cambia entre las muestras (el brillo de la celda salta)
+            let tangent = position.cross(Vec3::new(0.3, 0.9, 0.2)).normalize();
+            let numeric = (height(position + tangent * h) - height(position - tangent * h)) / (2.0 * h);
+            let half = (height(position + tangent * h * 0.5) - height(position - tangent * h * 0.5)) / h;
+            if (numeric - half).abs() > 0.05 * numeric.abs().max(1.0) {
+                continue;
+            }
+            // Las diferencias finitas en f32 tienen un error de ~1%: se exige que casi todas coincidan
+            let error = (gradient.dot(tangent) - numeric).abs();
+            compared += 1;
+            if error < 0.05 * numeric.abs().max(1.0) {
+                matching += 1;
+            }
+        }
+        assert!(compared > 150 && matching * 20 >= compared * 19, "{matching} de {compared}");
+    }
+
+    #[test]
+    fn looped_layers_repeat() {
+        let noise = NoiseGenerator::default();
+        let time_loop = TimeLoop::new(10.0);
+        let layer = SurfaceLayer::GRANULATION.looped(&time_loop);
+        assert_eq!(layer.repeat, 2);
+        // Rotación rígida con vueltas enteras por bucle (0.48 vueltas: se redondea a una)
+        let rotation = DifferentialRotation::new(time_loop.angular_frequency(0.3), 0.0, 0.0);
+        for position in sample_points() {
+            let (start, _) = granulation(&noise, None, position, 0.0, &layer, &rotation);
+            let (end, _) = granulation(&noise, None, position, time_loop.duration, &layer, &rotation);
+            assert!((start - end).abs() < 0.05, "{start} vs {end}");
+        }
+    }
+
+    #[test]
+    fn batches_match_the_scalar_layers() {
+        let noise = NoiseGenerator::new(6);
+        let mut uniforms = Uniforms::new();
+        uniforms.time = 17.3;
+        let points: Vec<Vec3> = sample_points().collect();
+        for (chunk, mu) in points.chunks_exact(4).zip([0.9, 0.5, 0.1].into_iter().cycle()) {
+            let positions = [chunk[0], chunk[1], chunk[2], chunk[3]];
+            let (time, rotation) = (uniforms.time, &uniforms.differential_rotation);
+            let granules = granulation_batch(&noise, None, &positions, time, &uniforms.granulation, rotation);
+            let intensity = photosphere_intensity_batch(&noise, None, &positions, granules.map(|(g, _)| g), [mu; 4], &uniforms);
+            for (i, &position) in positions.iter().enumerate() {
+                let (granule, relief) = granulation(&noise, None, position, time, &uniforms.granulation, rotation);
+                assert!((granules[i].0 - granule).abs() < 1e-4 && (granules[i].1 - relief).length() < 1e-2);
+                let scalar = photosphere_intensity(&noise, None, position, granule, mu, &uniforms);
+                assert!((intensity[i] - scalar).abs() < 1e-4, "{} vs {scalar}", intensity[i]);
+            }
+        }
+    }
+
+    #[test]
+    fn faculae_vanish_at_disk_center() {
+        for position in sample_points() {
+            let rotation = DifferentialRotation::default();
+            assert_eq!(faculae(&NoiseGenerator::default(), position, 1.0, 1.0, 3.0, &SurfaceLayer::FACULAE, &rotation), 0.0);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+/// Generador pseudoaleatorio SplitMix64
+/// Pequeño, rápido y determinista: la misma semilla produce siempre la misma secuencia,
+/// lo que permite reproducir exactamente una simulación
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Siguiente entero de 64 bits
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Flotante uniforme en [0, 1)
+    pub fn next_f32(&mut self) -> f32 {
+        // Se usan los 24 bits altos: exactamente representables en un f32
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Flotante uniforme en [min, max)
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    /// Entero uniforme en [0, n)
+    pub fn below(&mut self, n: u32) -> u32 {
+        (((self.next_u64() >> 32) * n as u64) >> 32) as u32
+    }
+
+    /// Normal estándar (media 0, desviación 1) por Box-Muller
+    pub fn gaussian(&mut self) -> f32 {
+        let u1 = (1.0 - self.next_f32()).max(f32::MIN_POSITIVE);
+        let u2 = self.next_f32();
+        (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+    }
+
+    /// `true` con probabilidad `p`
+    pub fn chance(&mut self, p: f32) -> bool {
+        self.next_f32() < p
+    }
+}
+
+/// Mezcla una semilla con un índice para obtener semillas independientes
+/// (por ejemplo, una por ciclo de actividad)
+pub fn derive_seed(seed: u64, index: u64) -> u64 {
+    Rng::new(seed ^ index.wrapping_mul(0xd1b5_4a32_d192_ed03)).next_u64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+    }
+
+    #[test]
+    fn uniform_and_gaussian_statistics() {
+        let mut rng = Rng::new(7);
+        let n = 20_000;
+        let uniform: Vec<f32> = (0..n).map(|_| rng.next_f32()).collect();
+        assert!(uniform.iter().all(|&x| (0.0..1.0).contains(&x)));
+        let mean = uniform.iter().sum::<f32>() / n as f32;
+        assert!((mean - 0.5).abs() < 0.01);
+
+        let normal: Vec<f32> = (0..n).map(|_| rng.gaussian()).collect();
+        let mean = normal.iter().sum::<f32>() / n as f32;
+        let variance = normal.iter().map(|x| (x - mean) * (x - mean)).sum::<f32>() / n as f32;
+        assert!(mean.abs() < 0.03);
+        assert!((variance - 1.0).abs() < 0.05);
+
+        assert!((0..1000).all(|_| rng.below(6) < 6));
+    }
+}
@@ -1,12 +1,89 @@
 use crate::framebuffer::Framebuffer;
 use crate::vertex::Vertex;
 use crate::uniforms::Uniforms;
-use crate::shaders::{vertex_shader, fragment_shader};
+use crate::shaders::Shader;
 use crate::triangle::triangle;
 
 /// PIPELINE DE RENDERIZADO COMPLETO
 /// Este es el proceso que transforma vértices 3D en píxeles en la pantalla
-pub fn render(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertex_array: &[Vertex]) {
+/// Las etapas programables (vértices y fragmentos) las aporta el shader
+pub fn render<S: Shader>(framebuffer: &mut Framebuffer, uniforms: &Uniforms, shader: &S, vertex_array: &[Vertex]) {
+    let triangles = assemble(uniforms, shader, vertex_array);
+
+    // ==========================================
+    // ETAPA 3: RASTERIZACIÓN
+    // Convierte cada triángulo en fragmentos (píxeles candidatos)
+    // Usa coordenadas baricéntricas para interpolar los varyings del shader
+    // ==========================================
+    let mut fragments = Vec::new();
+    for tri in &triangles {
+        fragments.extend(triangle(&tri[0], &tri[1], &tri[2]));
+    }
+
+    // ==========================================
+    // ETAPA 4: FRAGMENT SHADER
+    // Calcula el color final de cada fragmento
+    // Aplica iluminación, texturas, efectos, etc.
+    // Solo se sombrean los fragmentos que pasan la prueba de profundidad
+    // ==========================================
+    for fragment in fragments {
+        let x = fragment.position.x as i32;
+        let y = fragment.position.y as i32;
+        if !framebuffer.depth_test(x, y, fragment.depth) {
+            continue;
+        }
+        
+        let color = shader.fragment(&fragment, uniforms);
+        framebuffer.point(x, y, color);
+    }
+}
+
+/// Mismo pipeline, pero los fragmentos que pasan la prueba de profundidad se juntan
+/// en tramos de 4 (en orden de rasterización: casi siempre píxeles vecinos de una
+/// misma fila) y el fragment shader los sombrea juntos (`Shader::fragment_batch`):
+/// así el shader de la estrella evalúa su ruido en lotes SIMD sin carriles vacíos
+/// La imagen es la misma que con `render`
+pub fn render_spans<S: Shader>(framebuffer: &mut Framebuffer, uniforms: &Uniforms, shader: &S, vertex_array: &[Vertex]) {
+    let triangles = assemble(uniforms, shader, vertex_array);
+
+    // ==========================================
+    // ETAPA 3: RASTERIZACIÓN
+    // ==========================================
+    let mut fragments = Vec::new();
+    for tri in &triangles {
+        fragments.extend(triangle(&tri[0], &tri[1], &tri[2]));
+    }
+
+    // ==========================================
+    // ETAPA 4: FRAGMENT SHADER EN TRAMOS
+    // Los colores se escriben en el mismo orden que los fragmentos: si un píxel se
+    // vuelve a cubrir más adelante, gana el último, igual que en `render`
+    // Los que sobran al final (menos de 4) se sombrean uno por uno
+    // ==========================================
+    let mut span = Vec::with_capacity(4);
+    for fragment in &fragments {
+        let x = fragment.position.x as i32;
+        let y = fragment.position.y as i32;
+        if !framebuffer.depth_test(x, y, fragment.depth) {
+            continue;
+        }
+
+        span.push(fragment);
+        if let &[a, b, c, d] = span.as_slice() {
+            let colors = shader.fragment_batch([a, b, c, d], uniforms);
+            for (fragment, color) in span.drain(..).zip(colors) {
+                framebuffer.point(fragment.position.x as i32, fragment.position.y as i32, color);
+            }
+        }
+    }
+    for fragment in span {
+        let color = shader.fragment(fragment, uniforms);
+        framebuffer.point(fragment.position.x as i32, fragment.position.y as i32, color);
+    }
+}
+
+/// Etapas 1 y 2, comunes a los dos caminos de rasterización
+fn assemble<S: Shader>(uniforms: &Uniforms, shader: &S, vertex_array: &[Vertex]) -> Vec<[(Vertex, S::Varyings); 3]> {
     // ==========================================
     // ETAPA 1: VERTEX SHADER
     // Transforma cada vértice del espacio local al espacio de pantalla
@@ -14,7 +91,7 @@ pub fn render(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertex_array:
     // ==========================================
     let mut transformed_vertices = Vec::with_capacity(vertex_array.len());
     for vertex in vertex_array {
-        let transformed = vertex_shader(vertex, uniforms);
+        let transformed = shader.vertex(vertex, uniforms);
         transformed_vertices.push(transformed);
     }
 
@@ -34,27 +111,5 @@ pub fn render(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertex_array:
         }
     }
 
-    // ==========================================
-    // ETAPA 3: RASTERIZACIÓN
-    // Convierte cada triángulo en fragmentos (píxeles candidatos)
-    // Usa coordenadas baricéntricas para interpolar atributos
-    // ==========================================
-    let mut fragments = Vec::new();
-    for tri in &triangles {
-        fragments.extend(triangle(&tri[0], &tri[1], &tri[2]));
-    }
-
-    // ==========================================
-    // ETAPA 4: FRAGMENT SHADER
-    // Calcula el color final de cada fragmento
-    // Aplica iluminación, texturas, efectos, etc.
-    // ==========================================
-    for fragment in fragments {
-        let color = fragment_shader(&fragment, uniforms);
-        framebuffer.point(
-            fragment.position.x as i32,
-            fragment.position.y as i32,
-            color
-        );
-    }
+    triangles
 }
@@ -0,0 +1,281 @@
+use raylib::prelude::*;
+use crate::vertex::{Vertex, Fragment};
+use crate::uniforms::Uniforms;
+use crate::math::Vec3;
+use crate::noise::{BakedNoise, F32x4, NoiseGenerator, Simd, Vec3Batch};
+use crate::photosphere::{granulation, granulation_batch, photosphere_intensity, photosphere_intensity_batch};
+use crate::sunspots::sunspot_temperature;
+use crate::blackbody::{blackbody_color, relative_luminosity, linear_to_srgb};
+use crate::shaders::Shader;
+use crate::impl_interpolate;
+
+/// SHADER DE LA ESTRELLA
+/// Superficie solar procedural: granulación, manchas, pulsación y emisión variable
+/// Cada shader tiene su propio generador de ruido: semillas distintas dan estrellas distintas
+/// Con `baked` la turbulencia y el ruido celular se leen de volúmenes precalculados
+/// (más rápido, algo menos detalle); sin él se evalúan exactos en cada fragmento
+#[derive(Default)]
+pub struct StarShader {
+    pub noise: NoiseGenerator,
+    pub baked: Option<BakedNoise>,
+}
+
+impl StarShader {
+    pub fn new(seed: u64) -> Self {
+        Self { noise: NoiseGenerator::new(seed), baked: None }
+    }
+
+    /// Hornea los volúmenes de ruido con `resolution`³ vóxeles y pasa a usarlos
+    pub fn bake(&mut self, resolution: u32) {
+        self.baked = Some(BakedNoise::bake(&self.noise, resolution));
+    }
+}
+
+/// Datos que el vertex shader de la estrella pasa al fragment shader
+#[derive(Clone, Debug)]
+pub struct StarVaryings {
+    pub world_position: Vec3, // Posición en espacio mundo (ya distorsionada)
+    pub local_position: Vec3, // Posición en espacio objeto (el patrón gira con la estrella)
+    pub normal: Vec3,         // Normal en espacio mundo
+}
+
+impl_interpolate!(StarVaryings { world_position, local_position, normal });
+
+impl Shader for StarShader {
+    type Varyings = StarVaryings;
+
+    /// VERTEX SHADER
+    /// Transforma los vértices del espacio local al espacio de pantalla
+    /// Aplica las transformaciones de modelo, vista, proyección y viewport
+    fn vertex(&self, vertex: &Vertex, uniforms: &Uniforms) -> (Vertex, Self::Varyings) {
+        // Combinar todas las matrices de transformación
+        let mvp = uniforms.projection_matrix * uniforms.view_matrix * uniforms.model_matrix;
+        
+        // Transformar posición del vértice (se guarda w para la interpolación con perspectiva)
+        let position = Vec3::from(vertex.position);
+        let clip_position = mvp * position.extend(1.0);
+        
+        // Aplicar matriz de viewport para convertir a coordenadas de pantalla
+        let screen_position = uniforms.viewport_matrix.transform_point(clip_position.perspective_divide());
+        
+        // Calcular posición en espacio mundo (sin proyección)
+        let world_position = uniforms.model_matrix.transform_point(position);
+        
+        // Llevar la normal a espacio mundo con la matriz de normales
+        let world_normal = uniforms.normal_matrix.transform_vector(Vec3::from(vertex.normal)).normalize();
+        
+        // DISTORSIÓN DEL VERTEX SHADER - simula actividad solar
+        // (simplex 4D: el tiempo es la cuarta dimensión y la superficie hierve en su sitio)
+        let (drift, w) = uniforms.noise_time(0.5);
+        let noise_offset = self.noise.turbulence4(
+            world_position.x * 2.0,
+            world_position.y * 2.0,
+            world_position.z * 2.0 + drift,
+            w,
+            3
+        ) * 0.1 * uniforms.turbulence_intensity;
+        
+        // Aplicar distorsión a lo largo de la normal
+        let distorted_position = world_position + world_normal * noise_offset;
+        
+        let transformed = Vertex {
+            position: vertex.position,
+            normal: world_normal.into(),
+            transformed_position: screen_position.into(),
+            clip_w: clip_position.w,
+        };
+        let varyings = StarVaryings {
+            world_position: distorted_position,
+            local_position: position,
+            normal: world_normal,
+        };
+        
+        (transformed, varyings)
+    }
+
+    /// FRAGMENT SHADER
+    /// Calcula el color final de cada píxel
+    /// Implementa la apariencia de la estrella con ruido, colores dinámicos y emisión
+    fn fragment(&self, fragment: &Fragment<StarVaryings>, uniforms: &Uniforms) -> Color {
+        let surface = fragment.varyings.local_position.normalize();
+        let (granules, relief) = granulation(&self.noise, self.baked.as_ref(), surface, uniforms.time, &uniforms.granulation, &uniforms.differential_rotation);
+        let (mu, limb) = self.limb(&fragment.varyings, relief, uniforms);
+        let photosphere = photosphere_intensity(&self.noise, self.baked.as_ref(), surface, granules, mu, uniforms);
+        let flow = self.flow(surface, uniforms);
+        let (drift, w) = uniforms.noise_time(0.3);
+        let turbulence_value: f32 = flow
+            .iter()
+            .map(|&(coordinates, weight)| {
+                let p = coordinates * uniforms.noise_scale + Vec3::new(0.0, 0.0, drift);
+                weight * match &self.baked {
+                    Some(baked) => baked.turbulence(p, w),
+                    None => self.noise.turbulence_slices(p, w, BakedNoise::TURBULENCE_OCTAVES, BakedNoise::TURBULENCE_PERIOD),
+                }
+            })
+            .sum();
+        self.shade(&fragment.varyings, surface, limb, photosphere, turbulence_value, uniforms)
+    }
+
+    /// FRAGMENT SHADER EN LOTES
+    /// Las mismas etapas para 4 fragmentos, con el ruido de la fotosfera y de la
+    /// turbulencia evaluado en lotes SIMD; el resto se calcula fragmento por fragmento
+    fn fragment_batch(&self, fragments: [&Fragment<StarVaryings>; 4], uniforms: &Uniforms) -> [Color; 4] {
+        let surfaces = fragments.map(|fragment| fragment.varyings.local_position.normalize());
+        let granules = granulation_batch(&self.noise, self.baked.as_ref(), &surfaces, uniforms.time, &uniforms.granulation, &uniforms.differential_rotation);
+        let lanes: [(f32, f32); 4] = std::array::from_fn(|i| self.limb(&fragments[i].varyings, granules[i].1, uniforms));
+        let photosphere = photosphere_intensity_batch(&self.noise, self.baked.as_ref(), &surfaces, granules.map(|(g, _)| g), lanes.map(|(mu, _)| mu), uniforms);
+
+        let flows = surfaces.map(|surface| self.flow(surface, uniforms));
+        let (drift, w) = uniforms.noise_time(0.3);
+        let turbulence: [f32; 4] = match &self.baked {
+            Some(baked) => flows.map(|flow| {
+                flow.iter()
+                    .map(|&(coordinates, weight)| weight * baked.turbulence(coordinates * uniforms.noise_scale + Vec3::new(0.0, 0.0, drift), w))
+                    .sum()
+            }),
+            None => {
+                // Un lote por fase del flujo, cada uno con los 4 fragmentos
+                let phase = |n: usize| {
+                    let points: Vec3Batch<F32x4> = Vec3Batch::from_fn(|i| flows[i][n].0 * uniforms.noise_scale + Vec3::new(0.0, 0.0, drift));
+                    let turbulence = self.noise.turbulence_slices_batch(&points, w, BakedNoise::TURBULENCE_OCTAVES, BakedNoise::TURBULENCE_PERIOD);
+                    turbulence * F32x4::from_fn(|i| flows[i][n].1)
+                };
+                (phase(0) + phase(1)).to_array()
+            }
+        };
+
+        std::array::from_fn(|i| {
+            let (_, limb) = lanes[i];
+            self.shade(&fragments[i].varyings, surfaces[i], limb, photosphere[i], turbulence[i], uniforms)
+        })
+    }
+}
+
+impl StarShader {
+    /// 1. OSCURECIMIENTO HACIA EL LIMBO - depende del ángulo entre la normal y la vista
+    ///
+    /// μ = cos(θ) vale 1 en el centro del disco y 0 en el borde
+    /// La normal se inclina con el gradiente analítico del relieve de los gránulos
+    /// (bump mapping): cada gránulo se oscurece hacia el lado que no mira a la cámara
+    /// `relief` es el gradiente tangente de la propia granulación (`granulation`)
+    /// Retorna μ y el oscurecimiento
+    fn limb(&self, varyings: &StarVaryings, relief: Vec3, uniforms: &Uniforms) -> (f32, f32) {
+        let mut normal = varyings.normal.normalize();
+        if uniforms.granulation_relief > 0.0 {
+            let tilt = uniforms.normal_matrix.transform_vector(relief * uniforms.granulation_relief);
+            normal = (normal - tilt).normalize();
+        }
+        let mu = normal.dot(uniforms.view_direction(varyings.world_position)).max(0.0);
+        (mu, uniforms.limb_darkening.intensity(mu))
+    }
+
+    // 2. FOTOSFERA - granulación, supergranulación y fáculas sobre la esfera unitaria
+    // El ruido se muestrea en el marco que gira con la superficie a cada latitud,
+    // así los rasgos del ecuador adelantan a los de latitudes altas
+    // (`photosphere_intensity`, o `photosphere_intensity_batch` en lotes)
+
+    /// 3. RUIDO BASE - Turbulencia periódica suave para variaciones de gran escala
+    ///
+    /// Es un patrón de larga vida: se cizalla con toda la rotación diferencial
+    /// y evoluciona con el tiempo mezclando rebanadas del mismo campo (sin deslizarse
+    /// sobre la esfera, `turbulence_slices`); horneada se leen las mismas rebanadas del
+    /// volumen, así el interruptor de calidad no cambia el patrón ni su movimiento
+    /// Además lo arrastra una corriente de ruido curl: las coordenadas se advectan con
+    /// dos fases del flujo que se mezclan (sin divergencia, el plasma no se amontona)
+    /// Retorna las coordenadas de las dos fases con sus pesos; la turbulencia se suma
+    /// sobre ellas
+    fn flow(&self, surface: Vec3, uniforms: &Uniforms) -> [(Vec3, f32); 2] {
+        let rotating_surface = uniforms.differential_rotation.surface_frame(surface, uniforms.time) * 1.5;
+        uniforms.plasma_flow.flow_map(&self.noise, rotating_surface, uniforms.time, uniforms.plasma_flow_period)
+    }
+
+    /// Etapas 4 a 8: manchas, pulsación, temperatura y emisión a partir de lo anterior
+    fn shade(&self, varyings: &StarVaryings, surface: Vec3, limb: f32, photosphere: f32, turbulence_value: f32, uniforms: &Uniforms) -> Color {
+        let pos = varyings.world_position;
+        let time = uniforms.time;
+
+        // 4. MANCHAS SOLARES - umbra y penumbra más frías que la fotosfera
+        // (sus centros ya giran con la rotación diferencial)
+        let spots = sunspot_temperature(surface, &uniforms.sunspots);
+        
+        // 5. ANIMACIÓN DE PULSACIÓN - Simula pulsaciones de la estrella
+        let pulse = ((time * uniforms.frequency(2.0)).sin() * 0.5 + 0.5) * 0.2 + 0.8;
+        
+        // 6. CÁLCULO DE INTENSIDAD - Combina todos los efectos
+        // (la turbulencia suma ~0.94 en promedio con 4 octavas)
+        let base_intensity = (0.8 + photosphere + (turbulence_value - 0.94) * 0.15) * pulse;
+        let intensity = base_intensity.clamp(0.0, 1.0);
+        
+        // 7. TEMPERATURA LOCAL - Las zonas más intensas están más calientes
+        // (para el Sol: ~5200 K en los carriles y ~6350 K en los gránulos más brillantes)
+        // Dentro de las manchas se multiplica por su temperatura relativa
+        // El limbo se ve en capas más frías: como I ∝ T⁴, la temperatura aparente escala
+        // con la raíz cuarta del oscurecimiento, lo que además enrojece el borde
+        // El color sale del espectro de cuerpo negro y el brillo de Stefan-Boltzmann
+        let local_temperature = uniforms.star_temperature * (0.65 + 0.45 * intensity) * spots * limb.sqrt().sqrt();
+        let color = blackbody_color(local_temperature)
+            * relative_luminosity(local_temperature, uniforms.star_temperature);
+        
+        // 8. EMISIÓN VARIABLE - Picos de energía
+        let emission_boost = ((pos.x + pos.y + pos.z) * 10.0 + time * uniforms.frequency(5.0)).sin() * 0.2 + 1.0;
+        
+        // Color final con todos los efectos (se calcula en RGB lineal y se codifica en sRGB)
+        let final_r = linear_to_srgb(color.x * emission_boost);
+        let final_g = linear_to_srgb(color.y * emission_boost);
+        let final_b = linear_to_srgb(color.z * emission_boost);
+        
+        Color::new(
+            (final_r * 255.0) as u8,
+            (final_g * 255.0) as u8,
+            (final_b * 255.0) as u8,
+            255
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn baked_fragments_follow_the_exact_ones() {
+        let mut uniforms = Uniforms::new();
+        uniforms.camera_position = Vec3::new(0.0, 0.0, 5.0);
+        uniforms.noise_scale = 2.0;
+        let mut shader = StarShader::new(7);
+        // Fragmentos del hemisferio que mira a la cámara
+        let fragments: Vec<Fragment<StarVaryings>> = (0..400)
+            .map(|i| {
+                let t = i as f32 * 0.173;
+                let surface = Vec3::new(t.sin() * 0.8, (t * 0.37).cos() * 0.8, 1.0).normalize();
+                Fragment::new(0.0, 0.0, 0.5, StarVaryings { world_position: surface, local_position: surface, normal: surface })
+            })
+            .collect();
+
+        let shade = |shader: &StarShader, uniforms: &mut Uniforms| -> Vec<Color> {
+            [0.0, 3.7, 11.2]
+                .iter()
+                .flat_map(|&time| {
+                    uniforms.time = time;
+                    fragments.iter().map(|fragment| shader.fragment(fragment, uniforms)).collect::<Vec<_>>()
+                })
+                .collect()
+        };
+        let exact = shade(&shader, &mut uniforms);
+        // Misma resolución que la escena: el horneado solo pierde el detalle menor que un vóxel
+        shader.bake(128);
+        let baked = shade(&shader, &mut uniforms);
+
+        let (mut error, mut bias) = (0.0, 0.0);
+        for (baked, exact) in baked.iter().zip(&exact) {
+            for (a, b) in [(baked.r, exact.r), (baked.g, exact.g), (baked.b, exact.b)] {
+                error += (a as f32 - b as f32).abs();
+                bias += a as f32 - b as f32;
+            }
+        }
+        let n = exact.len() as f32 * 3.0;
+        // Error medio por canal (de 255) y diferencia de brillo media
+        assert!(error / n < 16.0, "error {}", error / n);
+        assert!((bias / n).abs() < 8.0, "sesgo {}", bias / n);
+    }
+}
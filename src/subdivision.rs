@@ -0,0 +1,342 @@
+use raylib::prelude::*;
+use crate::vertex::Vertex;
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+/// Esquemas de subdivisión disponibles para refinar las mallas cargadas
+/// Más densidad = la distorsión del vertex shader a lo largo de la normal se ve más suave
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubdivisionScheme {
+    Loop,         // Para mallas de triángulos
+    CatmullClark, // Para quads (funciona con cualquier polígono)
+}
+
+impl SubdivisionScheme {
+    /// Elige el esquema según las caras: Catmull-Clark si todas son quads, Loop en otro caso
+    pub fn for_faces(faces: &[Vec<usize>]) -> Self {
+        if !faces.is_empty() && faces.iter().all(|face| face.len() == 4) {
+            SubdivisionScheme::CatmullClark
+        } else {
+            SubdivisionScheme::Loop
+        }
+    }
+}
+
+/// Clave de arista independiente de la dirección
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    if a < b { (a, b) } else { (b, a) }
+}
+
+/// SUBDIVISIÓN DE LOOP (un nivel)
+/// Cada triángulo se divide en 4: se inserta un vértice en cada arista
+/// y los vértices originales se reposicionan con los pesos de Loop
+pub fn loop_subdivide(vertices: &[Vertex], indices: &[usize]) -> (Vec<Vertex>, Vec<usize>) {
+    // Para cada arista guardamos los vértices opuestos de los triángulos que la comparten
+    // (en orden de aparición, para que el resultado sea determinista)
+    let mut edge_index: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut edge_opposites: Vec<((usize, usize), Vec<usize>)> = Vec::new();
+    for tri in indices.chunks_exact(3) {
+        for i in 0..3 {
+            let a = tri[i];
+            let b = tri[(i + 1) % 3];
+            let c = tri[(i + 2) % 3];
+            let key = edge_key(a, b);
+            let index = *edge_index.entry(key).or_insert_with(|| {
+                edge_opposites.push((key, Vec::new()));
+                edge_opposites.len() - 1
+            });
+            edge_opposites[index].1.push(c);
+        }
+    }
+
+    // Vecinos de cada vértice y vecinos sobre el borde (aristas con un solo triángulo)
+    let mut neighbors: Vec<Vec<usize>> = vec![Vec::new(); vertices.len()];
+    let mut boundary_neighbors: Vec<Vec<usize>> = vec![Vec::new(); vertices.len()];
+    for &((a, b), ref opposites) in &edge_opposites {
+        neighbors[a].push(b);
+        neighbors[b].push(a);
+        if opposites.len() == 1 {
+            boundary_neighbors[a].push(b);
+            boundary_neighbors[b].push(a);
+        }
+    }
+
+    // 1. Reposicionar los vértices originales ("even vertices")
+    let mut new_vertices: Vec<Vertex> = Vec::with_capacity(vertices.len() + edge_opposites.len());
+    for (i, vertex) in vertices.iter().enumerate() {
+        let p = vertex.position;
+        let position = if !boundary_neighbors[i].is_empty() {
+            // Vértice de borde: 3/4 del original + 1/8 de cada vecino en el borde
+            let mut sum = Vector3::zero();
+            for &n in boundary_neighbors[i].iter().take(2) {
+                sum += vertices[n].position;
+            }
+            p * 0.75 + sum * 0.125
+        } else if neighbors[i].is_empty() {
+            p
+        } else {
+            let n = neighbors[i].len() as f32;
+            let inner = 0.375 + 0.25 * (2.0 * PI / n).cos();
+            let beta = (0.625 - inner * inner) / n;
+            let mut sum = Vector3::zero();
+            for &j in &neighbors[i] {
+                sum += vertices[j].position;
+            }
+            p * (1.0 - n * beta) + sum * beta
+        };
+        new_vertices.push(Vertex::new(position, vertex.normal));
+    }
+
+    // 2. Crear un vértice nuevo por arista ("odd vertices")
+    let first_edge_vertex = new_vertices.len();
+    for &((a, b), ref opposites) in &edge_opposites {
+        let pa = vertices[a].position;
+        let pb = vertices[b].position;
+        let position = if opposites.len() == 2 {
+            let pc = vertices[opposites[0]].position;
+            let pd = vertices[opposites[1]].position;
+            (pa + pb) * 0.375 + (pc + pd) * 0.125
+        } else {
+            (pa + pb) * 0.5
+        };
+        let normal = (vertices[a].normal + vertices[b].normal) * 0.5;
+        new_vertices.push(Vertex::new(position, normal));
+    }
+    let edge_vertex = |a: usize, b: usize| first_edge_vertex + edge_index[&edge_key(a, b)];
+
+    // 3. Cada triángulo original produce 4 triángulos con la misma orientación
+    let mut new_indices = Vec::with_capacity(indices.len() * 4);
+    for tri in indices.chunks_exact(3) {
+        let (a, b, c) = (tri[0], tri[1], tri[2]);
+        let ab = edge_vertex(a, b);
+        let bc = edge_vertex(b, c);
+        let ca = edge_vertex(c, a);
+        new_indices.extend_from_slice(&[a, ab, ca]);
+        new_indices.extend_from_slice(&[ab, b, bc]);
+        new_indices.extend_from_slice(&[ca, bc, c]);
+        new_indices.extend_from_slice(&[ab, bc, ca]);
+    }
+
+    (new_vertices, new_indices)
+}
+
+/// SUBDIVISIÓN DE CATMULL-CLARK (un nivel)
+/// Acepta polígonos de cualquier número de lados y siempre produce quads
+pub fn catmull_clark_subdivide(vertices: &[Vertex], faces: &[Vec<usize>]) -> (Vec<Vertex>, Vec<Vec<usize>>) {
+    // Punto de cara: promedio de los vértices de la cara
+    let face_points: Vec<Vector3> = faces.iter().map(|face| {
+        let mut sum = Vector3::zero();
+        for &i in face {
+            sum += vertices[i].position;
+        }
+        sum / face.len().max(1) as f32
+    }).collect();
+
+    // Caras que comparten cada arista (en orden de aparición)
+    let mut edge_index: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut edge_faces: Vec<((usize, usize), Vec<usize>)> = Vec::new();
+    for (f, face) in faces.iter().enumerate() {
+        for i in 0..face.len() {
+            let key = edge_key(face[i], face[(i + 1) % face.len()]);
+            let index = *edge_index.entry(key).or_insert_with(|| {
+                edge_faces.push((key, Vec::new()));
+                edge_faces.len() - 1
+            });
+            edge_faces[index].1.push(f);
+        }
+    }
+
+    // Información por vértice: caras adyacentes, aristas adyacentes y aristas de borde
+    let mut vertex_faces: Vec<Vec<usize>> = vec![Vec::new(); vertices.len()];
+    for (f, face) in faces.iter().enumerate() {
+        for &i in face {
+            vertex_faces[i].push(f);
+        }
+    }
+    let mut vertex_edges: Vec<Vec<usize>> = vec![Vec::new(); vertices.len()];
+    let mut boundary_neighbors: Vec<Vec<usize>> = vec![Vec::new(); vertices.len()];
+    for &((a, b), ref adjacent) in &edge_faces {
+        vertex_edges[a].push(b);
+        vertex_edges[b].push(a);
+        if adjacent.len() == 1 {
+            boundary_neighbors[a].push(b);
+            boundary_neighbors[b].push(a);
+        }
+    }
+
+    // 1. Reposicionar los vértices originales: (F + 2R + (n - 3)P) / n
+    let mut new_vertices: Vec<Vertex> = Vec::with_capacity(vertices.len() + faces.len() + edge_faces.len());
+    for (i, vertex) in vertices.iter().enumerate() {
+        let p = vertex.position;
+        let position = if !boundary_neighbors[i].is_empty() {
+            let mut sum = Vector3::zero();
+            for &n in boundary_neighbors[i].iter().take(2) {
+                sum += vertices[n].position;
+            }
+            p * 0.75 + sum * 0.125
+        } else if vertex_faces[i].is_empty() {
+            p
+        } else {
+            let n = vertex_faces[i].len() as f32;
+            let mut f = Vector3::zero();
+            for &face in &vertex_faces[i] {
+                f += face_points[face];
+            }
+            f /= n;
+            let mut r = Vector3::zero();
+            for &j in &vertex_edges[i] {
+                r += (p + vertices[j].position) * 0.5;
+            }
+            r /= vertex_edges[i].len() as f32;
+            (f + r * 2.0 + p * (n - 3.0)) / n
+        };
+        new_vertices.push(Vertex::new(position, vertex.normal));
+    }
+
+    // 2. Un vértice nuevo por cara
+    let face_start = new_vertices.len();
+    for (face, &point) in faces.iter().zip(&face_points) {
+        let mut normal = Vector3::zero();
+        for &i in face {
+            normal += vertices[i].normal;
+        }
+        new_vertices.push(Vertex::new(point, normal / face.len().max(1) as f32));
+    }
+
+    // 3. Un vértice nuevo por arista: promedio de los extremos y de los puntos de cara vecinos
+    let first_edge_vertex = new_vertices.len();
+    for &((a, b), ref adjacent) in &edge_faces {
+        let pa = vertices[a].position;
+        let pb = vertices[b].position;
+        let position = if adjacent.len() == 2 {
+            (pa + pb + face_points[adjacent[0]] + face_points[adjacent[1]]) * 0.25
+        } else {
+            (pa + pb) * 0.5
+        };
+        let normal = (vertices[a].normal + vertices[b].normal) * 0.5;
+        new_vertices.push(Vertex::new(position, normal));
+    }
+    let edge_vertex = |a: usize, b: usize| first_edge_vertex + edge_index[&edge_key(a, b)];
+
+    // 4. Cada cara de k lados produce k quads
+    let mut new_faces = Vec::new();
+    for (f, face) in faces.iter().enumerate() {
+        let k = face.len();
+        for i in 0..k {
+            let prev = face[(i + k - 1) % k];
+            let current = face[i];
+            let next = face[(i + 1) % k];
+            new_faces.push(vec![
+                current,
+                edge_vertex(current, next),
+                face_start + f,
+                edge_vertex(prev, current),
+            ]);
+        }
+    }
+
+    (new_vertices, new_faces)
+}
+
+/// Triangula polígonos en abanico (suficiente para polígonos convexos como los quads)
+pub fn triangulate(faces: &[Vec<usize>]) -> Vec<usize> {
+    let mut indices = Vec::new();
+    for face in faces {
+        for i in 1..face.len().saturating_sub(1) {
+            indices.push(face[0]);
+            indices.push(face[i]);
+            indices.push(face[i + 1]);
+        }
+    }
+    indices
+}
+
+/// Recalcula las normales de los vértices promediando las normales de las caras
+/// (ponderadas por área, porque el producto cruz no se normaliza antes de sumar)
+pub fn compute_normals(vertices: &mut [Vertex], indices: &[usize]) {
+    let mut normals = vec![Vector3::zero(); vertices.len()];
+
+    for tri in indices.chunks_exact(3) {
+        let a = vertices[tri[0]].position;
+        let b = vertices[tri[1]].position;
+        let c = vertices[tri[2]].position;
+        let face_normal = (b - a).cross(c - a);
+        for &i in tri {
+            normals[i] += face_normal;
+        }
+    }
+
+    for (vertex, normal) in vertices.iter_mut().zip(normals) {
+        let length = normal.length();
+        if length > 0.0 {
+            vertex.normal = normal / length;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vertices(positions: &[[f32; 3]]) -> Vec<Vertex> {
+        positions.iter().map(|&[x, y, z]| {
+            let position = Vector3::new(x, y, z);
+            Vertex::new(position, position.normalized())
+        }).collect()
+    }
+
+    /// Cuenta cuántas caras comparten cada arista
+    fn edge_counts(faces: &[Vec<usize>]) -> HashMap<(usize, usize), usize> {
+        let mut counts = HashMap::new();
+        for face in faces {
+            for i in 0..face.len() {
+                *counts.entry(edge_key(face[i], face[(i + 1) % face.len()])).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    #[test]
+    fn loop_splits_an_octahedron_into_a_closed_mesh() {
+        let octahedron = vertices(&[
+            [1.0, 0.0, 0.0], [-1.0, 0.0, 0.0], [0.0, 1.0, 0.0],
+            [0.0, -1.0, 0.0], [0.0, 0.0, 1.0], [0.0, 0.0, -1.0],
+        ]);
+        let indices = vec![
+            0, 2, 4, 2, 1, 4, 1, 3, 4, 3, 0, 4,
+            2, 0, 5, 1, 2, 5, 3, 1, 5, 0, 3, 5,
+        ];
+
+        let (new_vertices, new_indices) = loop_subdivide(&octahedron, &indices);
+        let triangles: Vec<Vec<usize>> = new_indices.chunks_exact(3).map(|tri| tri.to_vec()).collect();
+        let edges = edge_counts(&triangles);
+
+        // V' = V + E, F' = 4F, E' = 2E + 3F
+        assert_eq!(new_vertices.len(), 6 + 12);
+        assert_eq!(triangles.len(), 4 * 8);
+        assert_eq!(edges.len(), 2 * 12 + 3 * 8);
+        assert!(edges.values().all(|&count| count == 2));
+    }
+
+    #[test]
+    fn catmull_clark_turns_a_cube_into_a_closed_quad_mesh() {
+        let cube = vertices(&[
+            [-1.0, -1.0, -1.0], [1.0, -1.0, -1.0], [1.0, 1.0, -1.0], [-1.0, 1.0, -1.0],
+            [-1.0, -1.0, 1.0], [1.0, -1.0, 1.0], [1.0, 1.0, 1.0], [-1.0, 1.0, 1.0],
+        ]);
+        let faces = vec![
+            vec![0, 3, 2, 1], vec![4, 5, 6, 7], vec![0, 1, 5, 4],
+            vec![2, 3, 7, 6], vec![1, 2, 6, 5], vec![0, 4, 7, 3],
+        ];
+
+        let (new_vertices, new_faces) = catmull_clark_subdivide(&cube, &faces);
+        let edges = edge_counts(&new_faces);
+
+        // V' = V + F + E, cada cara de k lados da k quads, E' = 2E + suma de k
+        assert_eq!(new_vertices.len(), 8 + 6 + 12);
+        assert_eq!(new_faces.len(), 6 * 4);
+        assert!(new_faces.iter().all(|face| face.len() == 4));
+        assert_eq!(edges.len(), 2 * 12 + 6 * 4);
+        assert!(edges.values().all(|&count| count == 2));
+    }
+}
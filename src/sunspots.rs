@@ -0,0 +1,235 @@
+//! MANCHAS SOLARES
+//! Simulación de grupos de manchas a partir de una semilla:
+//! - Cada ciclo de actividad hace nacer grupos en bandas de latitud que migran hacia el
+//!   ecuador (ley de Spörer, el "diagrama de mariposa")
+//! - Los grupos son bipolares: una mancha líder y seguidoras inclinadas según la ley de Joy
+//! - Cada mancha tiene umbra oscura y penumbra filamentosa, crece rápido y decae despacio
+//! - Las manchas giran con la rotación diferencial de la superficie (más rápida en el ecuador)
+
+use std::f32::consts::{PI, TAU};
+use crate::math::{smoothstep, Vec3};
+use crate::noise::perlin_noise;
+use crate::random::{derive_seed, Rng};
+use crate::differential_rotation::DifferentialRotation;
+
+/// Temperatura de la umbra y de la penumbra relativas a la fotosfera
+/// (para el Sol: ~3900 K y ~5200 K)
+const UMBRA_TEMPERATURE: f32 = 0.68;
+const PENUMBRA_TEMPERATURE: f32 = 0.9;
+
+/// Parámetros de la simulación de manchas
+#[derive(Clone, Debug)]
+pub struct SunspotSystem {
+    pub seed: u64,
+    pub cycle_period: f32,     // Duración de un ciclo de actividad (segundos de animación)
+    pub groups_per_cycle: u32, // Grupos que nacen durante un ciclo completo
+    pub start_latitude: f32,   // Latitud de la banda activa al iniciar el ciclo (radianes)
+    pub end_latitude: f32,     // Latitud de la banda activa al terminar el ciclo (radianes)
+}
+
+/// Mancha visible en un instante dado
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sunspot {
+    pub center: Vec3,       // Centro sobre la esfera unitaria (espacio objeto)
+    pub radius: f32,        // Radio angular de la penumbra (radianes)
+    pub umbra_ratio: f32,   // Radio de la umbra / radio de la penumbra
+    pub filament_seed: f32, // Desfase del ruido de los filamentos
+}
+
+/// Mancha tal como nace: se evalúa en el tiempo con `SunspotSystem::active_spots`
+#[derive(Clone, Copy, Debug)]
+struct SpotRecord {
+    latitude: f32,
+    longitude: f32,
+    max_radius: f32,
+    umbra_ratio: f32,
+    birth: f32,
+    lifetime: f32,
+    filament_seed: f32,
+}
+
+/// Dirección sobre la esfera unitaria (Y es el eje de rotación)
+pub fn direction(latitude: f32, longitude: f32) -> Vec3 {
+    Vec3::new(
+        latitude.cos() * longitude.sin(),
+        latitude.sin(),
+        latitude.cos() * longitude.cos(),
+    )
+}
+
+impl SunspotSystem {
+    /// Ciclo de 2 minutos con bandas que van de 30° a 8° de latitud
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            cycle_period: 120.0,
+            groups_per_cycle: 80,
+            start_latitude: 30.0_f32.to_radians(),
+            end_latitude: 8.0_f32.to_radians(),
+        }
+    }
+
+    /// Latitud central de la banda activa en una fase del ciclo en [0, 1]
+    pub fn band_latitude(&self, phase: f32) -> f32 {
+        self.start_latitude + (self.end_latitude - self.start_latitude) * phase
+    }
+
+    /// Manchas que nacen durante el ciclo `cycle` (siempre las mismas para la misma semilla)
+    fn cycle_spots(&self, cycle: i64) -> Vec<SpotRecord> {
+        let mut rng = Rng::new(derive_seed(self.seed, cycle as u64));
+        let mut spots = Vec::new();
+
+        for _ in 0..self.groups_per_cycle {
+            // La actividad sube y baja a lo largo del ciclo: ∝ sin²(π · fase)
+            let phase = loop {
+                let candidate = rng.next_f32();
+                if rng.next_f32() < (PI * candidate).sin().powi(2) {
+                    break candidate;
+                }
+            };
+
+            let hemisphere = if rng.chance(0.5) { 1.0 } else { -1.0 };
+            let band = self.band_latitude(phase) + rng.gaussian() * 4.0_f32.to_radians();
+            let latitude = hemisphere * band.max(2.0_f32.to_radians());
+            let longitude = rng.range(0.0, TAU);
+            let birth = (cycle as f32 + phase) * self.cycle_period;
+            let lifetime = rng.range(0.08, 0.25) * self.cycle_period;
+            let leader_radius = rng.range(2.0, 5.0).to_radians();
+
+            // Ley de Joy: el grupo se inclina más cuanto mayor es la latitud,
+            // con las seguidoras más lejos del ecuador que la líder
+            let tilt = 0.5 * latitude.abs();
+            let members = 1 + rng.below(3);
+            let mut separation = 0.0;
+            for member in 0..members {
+                let radius = if member == 0 { leader_radius } else { leader_radius * rng.range(0.4, 0.8) };
+                spots.push(SpotRecord {
+                    latitude: latitude + hemisphere * separation * tilt.sin(),
+                    longitude: longitude - separation * tilt.cos(),
+                    max_radius: radius,
+                    umbra_ratio: rng.range(0.35, 0.5),
+                    birth: birth + rng.range(0.0, 0.05) * lifetime,
+                    lifetime: lifetime * rng.range(0.6, 1.0),
+                    filament_seed: rng.range(0.0, 100.0),
+                });
+                separation += leader_radius * rng.range(2.0, 3.5);
+            }
+        }
+
+        spots
+    }
+
+    /// Manchas visibles en el instante `time`, con su tamaño y posición actuales
+    /// La longitud de cada mancha avanza con la velocidad angular de su latitud
+    pub fn active_spots(&self, time: f32, rotation: &DifferentialRotation) -> Vec<Sunspot> {
+        let current_cycle = (time / self.cycle_period).floor() as i64;
+        let mut active = Vec::new();
+
+        // Las manchas del ciclo anterior pueden seguir vivas al empezar el siguiente
+        for cycle in current_cycle - 1..=current_cycle {
+            for spot in self.cycle_spots(cycle) {
+                let age = (time - spot.birth) / spot.lifetime;
+                if !(0.0..1.0).contains(&age) {
+                    continue;
+                }
+
+                // Crecimiento rápido y decaimiento lineal del área
+                let area = if age < 0.15 { age / 0.15 } else { 1.0 - (age - 0.15) / 0.85 };
+                let radius = spot.max_radius * area.sqrt();
+
+                let longitude = spot.longitude + rotation.angle(spot.latitude, time);
+
+                active.push(Sunspot {
+                    center: direction(spot.latitude, longitude),
+                    radius,
+                    umbra_ratio: spot.umbra_ratio,
+                    filament_seed: spot.filament_seed,
+                });
+            }
+        }
+
+        active
+    }
+}
+
+/// Temperatura relativa dentro de una mancha; `r` es la distancia angular al centro
+/// dividida entre el radio de la penumbra
+fn spot_profile(position: Vec3, spot: &Sunspot, cos_angle: f32, r: f32) -> f32 {
+    // Base tangente en el centro de la mancha para medir el ángulo alrededor de ella
+    let reference = if spot.center.y.abs() > 0.99 { Vec3::X } else { Vec3::Y };
+    let east = reference.cross(spot.center).normalize();
+    let north = spot.center.cross(east);
+    let offset = position - spot.center * cos_angle;
+    let angle = offset.dot(north).atan2(offset.dot(east));
+
+    // Filamentos radiales: ruido alrededor del círculo (periódico en el ángulo)
+    let filaments = perlin_noise(
+        angle.cos() * 8.0 + spot.filament_seed,
+        angle.sin() * 8.0,
+        r * 1.5,
+    );
+    let penumbra = PENUMBRA_TEMPERATURE + (filaments - 0.5) * 0.15;
+    let quiet_sun = smoothstep(0.85, 1.0, r);
+    let penumbra = penumbra + (1.0 - penumbra) * quiet_sun;
+
+    let umbra = 1.0 - smoothstep(spot.umbra_ratio * 0.85, spot.umbra_ratio * 1.1, r);
+    penumbra + (UMBRA_TEMPERATURE - penumbra) * umbra
+}
+
+/// Factor de temperatura en un punto de la esfera unitaria: 1 en la fotosfera tranquila,
+/// ~0.68 en la umbra
+pub fn sunspot_temperature(position: Vec3, spots: &[Sunspot]) -> f32 {
+    let mut factor: f32 = 1.0;
+
+    for spot in spots {
+        let cos_angle = position.dot(spot.center).clamp(-1.0, 1.0);
+        if cos_angle < spot.radius.cos() {
+            continue;
+        }
+        let r = cos_angle.acos() / spot.radius;
+        factor = factor.min(spot_profile(position, spot, cos_angle, r));
+    }
+
+    factor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spots_are_reproducible_from_seed() {
+        let rotation = DifferentialRotation::default();
+        let a = SunspotSystem::new(2024).active_spots(75.0, &rotation);
+        let b = SunspotSystem::new(2024).active_spots(75.0, &rotation);
+        let c = SunspotSystem::new(2025).active_spots(75.0, &rotation);
+        assert!(!a.is_empty());
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn active_latitudes_migrate_towards_equator() {
+        let system = SunspotSystem::new(11);
+        let spots = system.cycle_spots(0);
+        let mean_latitude = |from: f32, to: f32| {
+            let selected: Vec<f32> = spots
+                .iter()
+                .filter(|s| (from..to).contains(&(s.birth / system.cycle_period)))
+                .map(|s| s.latitude.abs())
+                .collect();
+            selected.iter().sum::<f32>() / selected.len() as f32
+        };
+        assert!(mean_latitude(0.0, 0.35) > mean_latitude(0.65, 1.0) + 5.0_f32.to_radians());
+    }
+
+    #[test]
+    fn umbra_is_darker_than_penumbra() {
+        let spot = Sunspot { center: Vec3::Z, radius: 0.1, umbra_ratio: 0.4, filament_seed: 0.0 };
+        let at = |angle: f32| sunspot_temperature(Vec3::new(angle.sin(), 0.0, angle.cos()), &[spot]);
+
+        assert!((at(0.0) - UMBRA_TEMPERATURE).abs() < 1e-3);
+        assert!(at(0.07) > at(0.0) && at(0.07) < 1.0);
+        assert_eq!(at(0.2), 1.0);
+    }
+}
@@ -0,0 +1,62 @@
+use crate::math::{Mat4, Quat, Vec3};
+
+/// Transformación de un objeto: traslación, rotación (cuaternión) y escala por eje
+/// Se compone en la matriz de modelo como T * R * S (primero escala, luego rota, luego traslada)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            translation: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            scale: Vec3::ONE,
+        }
+    }
+}
+
+impl Transform {
+    pub fn new(translation: Vec3, rotation: Quat, scale: Vec3) -> Self {
+        Self { translation, rotation, scale }
+    }
+
+    /// Matriz de modelo equivalente
+    pub fn to_matrix(self) -> Mat4 {
+        let mut matrix = self.rotation.to_matrix();
+        
+        // Escalar las columnas de la rotación equivale a R * S
+        for row in 0..3 {
+            matrix[row][0] *= self.scale.x;
+            matrix[row][1] *= self.scale.y;
+            matrix[row][2] *= self.scale.z;
+        }
+        
+        matrix[0][3] = self.translation.x;
+        matrix[1][3] = self.translation.y;
+        matrix[2][3] = self.translation.z;
+        
+        matrix
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matrix_applies_scale_then_rotation_then_translation() {
+        let transform = Transform::new(
+            Vec3::new(1.0, -2.0, 0.5),
+            Quat::from_axis_angle(Vec3::new(1.0, 1.0, 0.0), 0.8),
+            Vec3::new(2.0, 0.5, 1.5),
+        );
+        let point = Vec3::new(0.3, 0.9, -1.2);
+        let expected = transform.translation + transform.rotation * (point * transform.scale);
+        let actual = transform.to_matrix().transform_point(point);
+        assert!((actual - expected).length() < 1e-5);
+    }
+}
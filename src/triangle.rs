@@ -1,5 +1,5 @@
 use crate::vertex::{Vertex, Fragment};
-use raylib::prelude::*;
+use crate::interpolate::{Interpolate, perspective_correct_weights};
 
 /// Calcula las coordenadas baricéntricas de un punto P respecto a un triángulo ABC
 /// Las coordenadas baricéntricas son pesos que indican qué tan cerca está P de cada vértice
@@ -28,7 +28,12 @@ fn barycentric_coordinates(p_x: f32, p_y: f32, a: &Vertex, b: &Vertex, c: &Verte
 /// RASTERIZACIÓN DE TRIÁNGULO
 /// Convierte un triángulo en fragmentos (píxeles)
 /// Usa coordenadas baricéntricas para interpolar atributos
-pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex) -> Vec<Fragment> {
+/// Los varyings se interpolan con perspectiva correcta, sin importar su tipo
+pub fn triangle<V: Interpolate>(
+    (v1, varyings1): &(Vertex, V),
+    (v2, varyings2): &(Vertex, V),
+    (v3, varyings3): &(Vertex, V),
+) -> Vec<Fragment<V>> {
     let mut fragments = Vec::new();
 
     // Obtener coordenadas transformadas (en pantalla)
@@ -52,30 +57,16 @@ pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex) -> Vec<Fragment> {
 
             // Si el punto está dentro del triángulo (todas las coordenadas son positivas)
             if w >= 0.0 && v >= 0.0 && u >= 0.0 {
-                // Interpolar posición en espacio mundo usando coordenadas baricéntricas
-                let world_pos = Vector3::new(
-                    w * v1.world_position.x + v * v2.world_position.x + u * v3.world_position.x,
-                    w * v1.world_position.y + v * v2.world_position.y + u * v3.world_position.y,
-                    w * v1.world_position.z + v * v2.world_position.z + u * v3.world_position.z,
-                );
+                // Interpolar profundidad en espacio de pantalla (z después de la división
+                // de perspectiva, que varía linealmente sobre la pantalla)
+                let depth = w * v1.transformed_position.z + v * v2.transformed_position.z + u * v3.transformed_position.z;
 
-                // Interpolar normal
-                let normal = Vector3::new(
-                    w * v1.normal.x + v * v2.normal.x + u * v3.normal.x,
-                    w * v1.normal.y + v * v2.normal.y + u * v3.normal.y,
-                    w * v1.normal.z + v * v2.normal.z + u * v3.normal.z,
-                );
+                // Interpolar los varyings del shader con perspectiva correcta
+                let weights = perspective_correct_weights((w, v, u), (v1.clip_w, v2.clip_w, v3.clip_w));
+                let varyings = V::interpolate(varyings1, varyings2, varyings3, weights);
 
                 // Crear fragmento con todos los datos interpolados
-                let mut fragment = Fragment::new(
-                    x as f32,
-                    y as f32,
-                    Vector3::new(1.0, 1.0, 1.0),
-                    world_pos.z,
-                );
-                fragment.world_position = world_pos;
-                fragment.normal = normal;
-                
+                let fragment = Fragment::new(x as f32, y as f32, depth, varyings);
                 fragments.push(fragment);
             }
         }
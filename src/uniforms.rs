@@ -1,174 +1,388 @@
-use raylib::prelude::*;
+use crate::math::{Mat4, Quat, Vec3};
+use crate::blackbody::SUN_TEMPERATURE;
+use crate::limb_darkening::LimbDarkening;
+use crate::photosphere::SurfaceLayer;
+use crate::sunspots::Sunspot;
+use crate::differential_rotation::DifferentialRotation;
+use crate::noise::{CurlField, TimeLoop};
 
 /// Uniforms: datos que se pasan a los shaders y se mantienen constantes para todos los vértices
 pub struct Uniforms {
-    pub model_matrix: [[f32; 4]; 4],      // Matriz de modelo (transformación del objeto)
-    pub view_matrix: [[f32; 4]; 4],       // Matriz de vista (cámara)
-    pub projection_matrix: [[f32; 4]; 4], // Matriz de proyección
-    pub viewport_matrix: [[f32; 4]; 4],   // Matriz de viewport (pantalla)
-    pub time: f32,                         // Tiempo para animación
-    pub noise_scale: f32,                  // Escala del ruido
-    pub turbulence_intensity: f32,         // Intensidad de turbulencia
+    pub model_matrix: Mat4,      // Matriz de modelo (transformación del objeto)
+    pub normal_matrix: Mat4,     // Inversa transpuesta del modelo (transforma normales)
+    pub view_matrix: Mat4,       // Matriz de vista (cámara)
+    pub projection_matrix: Mat4, // Matriz de proyección
+    pub viewport_matrix: Mat4,   // Matriz de viewport (pantalla)
+    pub time: f32,               // Tiempo para animación
+    pub noise_scale: f32,        // Escala del ruido
+    pub turbulence_intensity: f32, // Intensidad de turbulencia
+    pub star_temperature: f32,   // Temperatura efectiva de la estrella (Kelvin)
+    pub camera_position: Vec3,   // Posición de la cámara en espacio mundo
+    pub limb_darkening: LimbDarkening, // Modelo de oscurecimiento hacia el limbo
+    pub granulation: SurfaceLayer,      // Celdas de convección pequeñas
+    pub granulation_relief: f32,        // Altura del relieve de los gránulos (0 = normales lisas)
+    pub supergranulation: SurfaceLayer, // Celdas de convección grandes
+    pub faculae: SurfaceLayer,          // Regiones brillantes cerca del limbo
+    pub sunspots: Vec<Sunspot>,         // Manchas visibles en este cuadro
+    pub differential_rotation: DifferentialRotation, // Velocidad angular de la superficie por latitud
+    pub plasma_flow: CurlField,         // Corriente de plasma que arrastra la turbulencia de la superficie
+    pub plasma_flow_period: f32,        // Segundos de arrastre antes de reiniciar cada fase del flujo
+    pub time_loop: Option<TimeLoop>,    // Bucle de animación (None = el tiempo avanza sin fin)
 }
 
 impl Uniforms {
     pub fn new() -> Self {
         Self {
-            model_matrix: identity_matrix(),
-            view_matrix: identity_matrix(),
-            projection_matrix: identity_matrix(),
-            viewport_matrix: identity_matrix(),
+            model_matrix: Mat4::IDENTITY,
+            normal_matrix: Mat4::IDENTITY,
+            view_matrix: Mat4::IDENTITY,
+            projection_matrix: Mat4::IDENTITY,
+            viewport_matrix: Mat4::IDENTITY,
             time: 0.0,
             noise_scale: 1.0,
             turbulence_intensity: 1.0,
+            star_temperature: SUN_TEMPERATURE,
+            camera_position: Vec3::ZERO,
+            limb_darkening: LimbDarkening::SUN_QUADRATIC,
+            granulation: SurfaceLayer::GRANULATION,
+            granulation_relief: 0.004,
+            supergranulation: SurfaceLayer::SUPERGRANULATION,
+            faculae: SurfaceLayer::FACULAE,
+            sunspots: Vec::new(),
+            differential_rotation: DifferentialRotation::default(),
+            plasma_flow: CurlField::new(1.5, 0.04, 0.05),
+            plasma_flow_period: 6.0,
+            time_loop: None,
         }
     }
-}
+    
+    /// Asigna la matriz de modelo y recalcula la matriz de normales
+    /// Se llama una vez por cuadro, no una vez por vértice
+    pub fn set_model_matrix(&mut self, model: Mat4) {
+        self.model_matrix = model;
+        self.normal_matrix = create_normal_matrix(&model);
+    }
 
-/// Crea una matriz identidad 4x4
-fn identity_matrix() -> [[f32; 4]; 4] {
-    [
-        [1.0, 0.0, 0.0, 0.0],
-        [0.0, 1.0, 0.0, 0.0],
-        [0.0, 0.0, 1.0, 0.0],
-        [0.0, 0.0, 0.0, 1.0],
-    ]
+    /// Avance del ruido animado a `speed` unidades por segundo: (0, time · speed) en una
+    /// dimensión del ruido, o un círculo en dos dimensiones si la animación está en bucle
+    pub fn noise_time(&self, speed: f32) -> (f32, f32) {
+        match &self.time_loop {
+            Some(time_loop) => time_loop.circle(self.time, speed),
+            None => (0.0, self.time * speed),
+        }
+    }
+
+    /// Frecuencia angular de una oscilación, ajustada a vueltas enteras si hay bucle
+    pub fn frequency(&self, omega: f32) -> f32 {
+        self.time_loop.map_or(omega, |time_loop| time_loop.angular_frequency(omega))
+    }
+
+    /// La proyección ortográfica no divide entre w: su última fila es (0, 0, 0, 1)
+    pub fn is_orthographic(&self) -> bool {
+        let projection = &self.projection_matrix;
+        projection[3][2] == 0.0 && projection[3][3] == 1.0
+    }
+
+    /// Lleva un punto del espacio mundo a la pantalla (x, y en píxeles, z profundidad)
+    /// Retorna None si el punto queda detrás de la cámara
+    pub fn world_to_screen(&self, world_position: Vec3) -> Option<Vec3> {
+        let clip = self.projection_matrix * self.view_matrix * world_position.extend(1.0);
+        if clip.w <= 0.0 {
+            return None;
+        }
+        Some(self.viewport_matrix.transform_point(clip.perspective_divide()))
+    }
+
+    /// Dirección (normalizada) desde un punto en espacio mundo hacia el observador
+    /// Con proyección ortográfica todos los rayos son paralelos: se usa el eje de la cámara
+    pub fn view_direction(&self, world_position: Vec3) -> Vec3 {
+        if self.is_orthographic() {
+            // La tercera fila de la matriz de vista es el eje -adelante de la cámara
+            self.view_matrix.row(2).truncate().normalize()
+        } else {
+            (self.camera_position - world_position).normalize()
+        }
+    }
 }
 
+// Todas las rotaciones siguen la regla de la mano derecha (como `Quat`): un ángulo
+// positivo gira en sentido antihorario visto desde la punta del eje
+// La escena compone su matriz de modelo con `Transform`; las funciones que no usa se
+// conservan como biblioteca
+
 /// Crea una matriz de rotación en Y
-pub fn create_rotation_y_matrix(angle: f32) -> [[f32; 4]; 4] {
+#[allow(dead_code)]
+pub fn create_rotation_y_matrix(angle: f32) -> Mat4 {
     let cos = angle.cos();
     let sin = angle.sin();
     
-    [
-        [cos, 0.0, -sin, 0.0],
+    Mat4::from_rows([
+        [cos, 0.0, sin, 0.0],
         [0.0, 1.0, 0.0, 0.0],
-        [sin, 0.0, cos, 0.0],
+        [-sin, 0.0, cos, 0.0],
         [0.0, 0.0, 0.0, 1.0],
-    ]
+    ])
 }
 
 /// Crea una matriz de rotación en X
-pub fn create_rotation_x_matrix(angle: f32) -> [[f32; 4]; 4] {
+#[allow(dead_code)]
+pub fn create_rotation_x_matrix(angle: f32) -> Mat4 {
     let cos = angle.cos();
     let sin = angle.sin();
     
-    [
+    Mat4::from_rows([
         [1.0, 0.0, 0.0, 0.0],
-        [0.0, cos, sin, 0.0],
-        [0.0, -sin, cos, 0.0],
+        [0.0, cos, -sin, 0.0],
+        [0.0, sin, cos, 0.0],
         [0.0, 0.0, 0.0, 1.0],
-    ]
+    ])
+}
+
+/// Crea una matriz de rotación en Z
+#[allow(dead_code)]
+pub fn create_rotation_z_matrix(angle: f32) -> Mat4 {
+    let cos = angle.cos();
+    let sin = angle.sin();
+    
+    Mat4::from_rows([
+        [cos, -sin, 0.0, 0.0],
+        [sin, cos, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
+
+/// Crea una matriz de rotación alrededor de un eje arbitrario
+#[allow(dead_code)]
+pub fn create_rotation_axis_matrix(axis: Vec3, angle: f32) -> Mat4 {
+    Quat::from_axis_angle(axis, angle).to_matrix()
 }
 
 /// Crea una matriz de escala
-pub fn create_scale_matrix(scale: f32) -> [[f32; 4]; 4] {
-    [
-        [scale, 0.0, 0.0, 0.0],
-        [0.0, scale, 0.0, 0.0],
-        [0.0, 0.0, scale, 0.0],
+#[allow(dead_code)]
+pub fn create_scale_matrix(scale: f32) -> Mat4 {
+    create_non_uniform_scale_matrix(scale, scale, scale)
+}
+
+/// Crea una matriz de escala distinta en cada eje
+#[allow(dead_code)]
+pub fn create_non_uniform_scale_matrix(x: f32, y: f32, z: f32) -> Mat4 {
+    Mat4::from_rows([
+        [x, 0.0, 0.0, 0.0],
+        [0.0, y, 0.0, 0.0],
+        [0.0, 0.0, z, 0.0],
         [0.0, 0.0, 0.0, 1.0],
-    ]
+    ])
+}
+
+/// Crea la matriz de normales: inversa transpuesta del modelo
+/// Con escala no uniforme la matriz de modelo deja de preservar ángulos y las
+/// normales transformadas con ella dejan de ser perpendiculares a la superficie
+pub fn create_normal_matrix(model: &Mat4) -> Mat4 {
+    match model.inverse() {
+        Some(inverse) => {
+            // La traslación no afecta a las normales
+            let mut normal_matrix = inverse.transpose();
+            normal_matrix[0][3] = 0.0;
+            normal_matrix[1][3] = 0.0;
+            normal_matrix[2][3] = 0.0;
+            normal_matrix[3] = [0.0, 0.0, 0.0, 1.0];
+            normal_matrix
+        }
+        None => Mat4::IDENTITY,
+    }
 }
 
 /// Crea una matriz de traslación
-pub fn create_translation_matrix(x: f32, y: f32, z: f32) -> [[f32; 4]; 4] {
-    [
+#[allow(dead_code)]
+pub fn create_translation_matrix(x: f32, y: f32, z: f32) -> Mat4 {
+    Mat4::from_rows([
         [1.0, 0.0, 0.0, x],
         [0.0, 1.0, 0.0, y],
         [0.0, 0.0, 1.0, z],
         [0.0, 0.0, 0.0, 1.0],
-    ]
+    ])
 }
 
 /// Crea una matriz de vista (cámara)
-pub fn create_view_matrix(eye: Vector3, center: Vector3, up: Vector3) -> [[f32; 4]; 4] {
-    let f = vector3_normalize(vector3_subtract(center, eye));
-    let s = vector3_normalize(vector3_cross_product(f, up));
-    let u = vector3_cross_product(s, f);
-
-    [
-        [s.x, s.y, s.z, -vector3_dot_product(s, eye)],
-        [u.x, u.y, u.z, -vector3_dot_product(u, eye)],
-        [-f.x, -f.y, -f.z, vector3_dot_product(f, eye)],
+pub fn create_view_matrix(eye: Vec3, center: Vec3, up: Vec3) -> Mat4 {
+    let f = (center - eye).normalize();
+    let s = f.cross(up).normalize();
+    let u = s.cross(f);
+
+    Mat4::from_rows([
+        [s.x, s.y, s.z, -s.dot(eye)],
+        [u.x, u.y, u.z, -u.dot(eye)],
+        [-f.x, -f.y, -f.z, f.dot(eye)],
         [0.0, 0.0, 0.0, 1.0],
-    ]
+    ])
 }
 
 /// Crea una matriz de proyección perspectiva
-pub fn create_perspective_matrix(fov: f32, aspect: f32, near: f32, far: f32) -> [[f32; 4]; 4] {
+pub fn create_perspective_matrix(fov: f32, aspect: f32, near: f32, far: f32) -> Mat4 {
     let f = 1.0 / (fov / 2.0).tan();
     
-    [
+    Mat4::from_rows([
         [f / aspect, 0.0, 0.0, 0.0],
         [0.0, f, 0.0, 0.0],
         [0.0, 0.0, (far + near) / (near - far), (2.0 * far * near) / (near - far)],
         [0.0, 0.0, -1.0, 0.0],
-    ]
+    ])
+}
+
+/// Crea una matriz de proyección perspectiva con Z invertida
+/// El plano cercano va a profundidad 1 y el lejano a 0: combinado con la precisión
+/// de los flotantes cerca de 0, reparte mucho mejor la precisión a lo largo de la escena
+pub fn create_reverse_z_perspective_matrix(fov: f32, aspect: f32, near: f32, far: f32) -> Mat4 {
+    let f = 1.0 / (fov / 2.0).tan();
+    
+    Mat4::from_rows([
+        [f / aspect, 0.0, 0.0, 0.0],
+        [0.0, f, 0.0, 0.0],
+        [0.0, 0.0, near / (far - near), (far * near) / (far - near)],
+        [0.0, 0.0, -1.0, 0.0],
+    ])
+}
+
+/// Crea una matriz de proyección perspectiva sin plano lejano (far -> infinito)
+/// Con `reverse_z` el infinito queda en profundidad 0 y el plano cercano en 1
+pub fn create_infinite_perspective_matrix(fov: f32, aspect: f32, near: f32, reverse_z: bool) -> Mat4 {
+    let f = 1.0 / (fov / 2.0).tan();
+    let (z_scale, z_offset) = if reverse_z { (0.0, near) } else { (-1.0, -2.0 * near) };
+    
+    Mat4::from_rows([
+        [f / aspect, 0.0, 0.0, 0.0],
+        [0.0, f, 0.0, 0.0],
+        [0.0, 0.0, z_scale, z_offset],
+        [0.0, 0.0, -1.0, 0.0],
+    ])
+}
+
+/// Crea una matriz de proyección ortográfica (sin perspectiva: el tamaño no depende de la distancia)
+pub fn create_orthographic_matrix(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Mat4 {
+    Mat4::from_rows([
+        [2.0 / (right - left), 0.0, 0.0, -(right + left) / (right - left)],
+        [0.0, 2.0 / (top - bottom), 0.0, -(top + bottom) / (top - bottom)],
+        [0.0, 0.0, -2.0 / (far - near), -(far + near) / (far - near)],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
+
+/// Crea una matriz de proyección ortográfica con Z invertida (cercano = 1, lejano = 0)
+pub fn create_reverse_z_orthographic_matrix(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Mat4 {
+    Mat4::from_rows([
+        [2.0 / (right - left), 0.0, 0.0, -(right + left) / (right - left)],
+        [0.0, 2.0 / (top - bottom), 0.0, -(top + bottom) / (top - bottom)],
+        [0.0, 0.0, 1.0 / (far - near), far / (far - near)],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
 }
 
 /// Crea una matriz de viewport
-pub fn create_viewport_matrix(width: f32, height: f32) -> [[f32; 4]; 4] {
-    [
+pub fn create_viewport_matrix(width: f32, height: f32) -> Mat4 {
+    Mat4::from_rows([
         [width / 2.0, 0.0, 0.0, width / 2.0],
         [0.0, -height / 2.0, 0.0, height / 2.0],
         [0.0, 0.0, 1.0, 0.0],
         [0.0, 0.0, 0.0, 1.0],
-    ]
+    ])
 }
 
-/// Multiplica dos matrices 4x4
-pub fn multiply_matrices(a: &[[f32; 4]; 4], b: &[[f32; 4]; 4]) -> [[f32; 4]; 4] {
-    let mut result = [[0.0; 4]; 4];
-    
-    for i in 0..4 {
-        for j in 0..4 {
-            for k in 0..4 {
-                result[i][j] += a[i][k] * b[k][j];
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normal_matrix_keeps_normals_perpendicular_under_non_uniform_scale() {
+        let model = create_rotation_y_matrix(0.7)
+            * create_rotation_x_matrix(0.3)
+            * create_non_uniform_scale_matrix(2.0, 0.5, 1.0);
+        let normal_matrix = create_normal_matrix(&model);
+
+        // Plano inclinado: la normal es perpendicular a sus dos tangentes
+        let normal = Vec3::new(1.0, 1.0, 0.0).normalize();
+        let tangents = [Vec3::new(1.0, -1.0, 0.0), Vec3::new(0.0, 0.0, 1.0)];
+
+        let transformed_normal = normal_matrix.transform_vector(normal).normalize();
+        for tangent in tangents {
+            let transformed_tangent = model.transform_vector(tangent);
+            assert!(transformed_normal.dot(transformed_tangent).abs() < 1e-5);
         }
     }
-    
-    result
-}
 
-/// Multiplica una matriz 4x4 por un vector (usado para transformar vértices)
-pub fn multiply_matrix_vector(matrix: &[[f32; 4]; 4], vector: Vector3) -> Vector3 {
-    let x = matrix[0][0] * vector.x + matrix[0][1] * vector.y + matrix[0][2] * vector.z + matrix[0][3];
-    let y = matrix[1][0] * vector.x + matrix[1][1] * vector.y + matrix[1][2] * vector.z + matrix[1][3];
-    let z = matrix[2][0] * vector.x + matrix[2][1] * vector.y + matrix[2][2] * vector.z + matrix[2][3];
-    let w = matrix[3][0] * vector.x + matrix[3][1] * vector.y + matrix[3][2] * vector.z + matrix[3][3];
-    
-    // Dividir por w para perspectiva correcta
-    if w != 0.0 {
-        Vector3::new(x / w, y / w, z / w)
-    } else {
-        Vector3::new(x, y, z)
+    #[test]
+    fn normal_matrix_ignores_translation() {
+        let model = create_translation_matrix(3.0, -2.0, 1.0) * create_scale_matrix(1.5);
+        let normal = normal_matrix_of(&model).transform_vector(Vec3::Y).normalize();
+        assert!((normal - Vec3::Y).length() < 1e-6);
     }
-}
 
-// Funciones auxiliares de vectores
-fn vector3_subtract(a: Vector3, b: Vector3) -> Vector3 {
-    Vector3::new(a.x - b.x, a.y - b.y, a.z - b.z)
-}
+    /// Profundidad (z después de la división de perspectiva) de un punto a `distance` frente a la cámara
+    fn depth_at(projection: &Mat4, distance: f32) -> f32 {
+        projection.transform_point(Vec3::new(0.0, 0.0, -distance)).z
+    }
 
-fn vector3_normalize(v: Vector3) -> Vector3 {
-    let length = (v.x * v.x + v.y * v.y + v.z * v.z).sqrt();
-    if length > 0.0 {
-        Vector3::new(v.x / length, v.y / length, v.z / length)
-    } else {
-        v
+    #[test]
+    fn perspective_maps_near_and_far_planes() {
+        let projection = create_perspective_matrix(1.0, 1.5, 0.1, 100.0);
+        assert!((depth_at(&projection, 0.1) + 1.0).abs() < 1e-4);
+        assert!((depth_at(&projection, 100.0) - 1.0).abs() < 1e-4);
     }
-}
 
-fn vector3_cross_product(a: Vector3, b: Vector3) -> Vector3 {
-    Vector3::new(
-        a.y * b.z - a.z * b.y,
-        a.z * b.x - a.x * b.z,
-        a.x * b.y - a.y * b.x,
-    )
-}
+    #[test]
+    fn reverse_z_perspective_maps_near_to_one_and_far_to_zero() {
+        let projection = create_reverse_z_perspective_matrix(1.0, 1.5, 0.1, 100.0);
+        assert!((depth_at(&projection, 0.1) - 1.0).abs() < 1e-4);
+        assert!(depth_at(&projection, 100.0).abs() < 1e-4);
+        assert!(depth_at(&projection, 5.0) > depth_at(&projection, 6.0));
+    }
+
+    #[test]
+    fn infinite_perspective_never_reaches_far_plane() {
+        let standard = create_infinite_perspective_matrix(1.0, 1.5, 0.1, false);
+        assert!((depth_at(&standard, 0.1) + 1.0).abs() < 1e-4);
+        assert!(depth_at(&standard, 1.0e6) < 1.0);
+        assert!(depth_at(&standard, 5.0) < depth_at(&standard, 6.0));
 
-fn vector3_dot_product(a: Vector3, b: Vector3) -> f32 {
-    a.x * b.x + a.y * b.y + a.z * b.z
+        let reversed = create_infinite_perspective_matrix(1.0, 1.5, 0.1, true);
+        assert!((depth_at(&reversed, 0.1) - 1.0).abs() < 1e-4);
+        assert!(depth_at(&reversed, 1.0e6) > 0.0);
+        assert!(depth_at(&reversed, 5.0) > depth_at(&reversed, 6.0));
+    }
+
+    #[test]
+    fn orthographic_maps_box_to_clip_cube() {
+        let projection = create_orthographic_matrix(-2.0, 2.0, -1.0, 1.0, 0.5, 10.0);
+        let corner = projection.transform_point(Vec3::new(2.0, 1.0, -0.5));
+        assert!((corner - Vec3::new(1.0, 1.0, -1.0)).length() < 1e-5);
+        assert!((depth_at(&projection, 10.0) - 1.0).abs() < 1e-5);
+
+        let reversed = create_reverse_z_orthographic_matrix(-2.0, 2.0, -1.0, 1.0, 0.5, 10.0);
+        assert!((depth_at(&reversed, 0.5) - 1.0).abs() < 1e-5);
+        assert!(depth_at(&reversed, 10.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn axis_rotations_match_the_quaternion_convention() {
+        let point = Vec3::new(0.4, -1.2, 0.7);
+        for angle in [0.3, -1.1, 2.5] {
+            for (axis, matrix) in [
+                (Vec3::X, create_rotation_x_matrix(angle)),
+                (Vec3::Y, create_rotation_y_matrix(angle)),
+                (Vec3::Z, create_rotation_z_matrix(angle)),
+            ] {
+                let expected = create_rotation_axis_matrix(axis, angle).transform_point(point);
+                assert!((matrix.transform_point(point) - expected).length() < 1e-5);
+            }
+        }
+        // Mano derecha: un cuarto de vuelta en Y lleva +X a -Z
+        let turned = create_rotation_y_matrix(std::f32::consts::FRAC_PI_2).transform_point(Vec3::X);
+        assert!((turned + Vec3::Z).length() < 1e-5);
+    }
+
+    fn normal_matrix_of(model: &Mat4) -> Mat4 {
+        let mut uniforms = Uniforms::new();
+        uniforms.set_model_matrix(*model);
+        uniforms.normal_matrix
+    }
 }
@@ -6,7 +6,7 @@ pub struct Vertex {
     pub position: Vector3,              // Posición original en espacio local
     pub normal: Vector3,                // Normal del vértice
     pub transformed_position: Vector3,   // Posición después de transformaciones
-    pub world_position: Vector3,         // Posición en espacio mundo
+    pub clip_w: f32,                     // w en clip space (para interpolar con perspectiva correcta)
 }
 
 impl Vertex {
@@ -15,29 +15,27 @@ impl Vertex {
             position,
             normal,
             transformed_position: position,
-            world_position: position,
+            clip_w: 1.0,
         }
     }
 }
 
 /// Estructura que representa un fragmento (píxel) durante la rasterización
+/// Todo lo demás (color, posición en mundo, normal, UVs...) lo declara el shader
+/// en sus varyings `V`, que el rasterizador interpola
 #[derive(Clone, Debug)]
-pub struct Fragment {
+pub struct Fragment<V = ()> {
     pub position: Vector2,       // Posición en pantalla
-    pub color: Vector3,          // Color base del fragmento
-    pub world_position: Vector3, // Posición en espacio mundo
-    pub normal: Vector3,         // Normal interpolada
     pub depth: f32,              // Profundidad del fragmento
+    pub varyings: V,             // Datos del shader interpolados
 }
 
-impl Fragment {
-    pub fn new(x: f32, y: f32, color: Vector3, depth: f32) -> Self {
+impl<V> Fragment<V> {
+    pub fn new(x: f32, y: f32, depth: f32, varyings: V) -> Self {
         Self {
             position: Vector2::new(x, y),
-            color,
-            world_position: Vector3::new(x, y, depth),
-            normal: Vector3::new(0.0, 0.0, 1.0),
             depth,
+            varyings,
         }
     }
 }
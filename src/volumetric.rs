@@ -0,0 +1,223 @@
+//! CORONA VOLUMÉTRICA Y PROTUBERANCIAS
+//! Pasada de ray marching sobre una cáscara alrededor de la estrella: por cada píxel se
+//! lanza un rayo desde la cámara y se recorre un campo de densidad 3D (ruido ridged)
+//! acumulando emisión y absorción. Como el campo vive en el espacio de la estrella, los
+//! lazos tienen paralaje real al mover la cámara, y los rayos se detienen en el buffer de
+//! profundidad para que las partes detrás de la esfera queden ocultas
+
+use raylib::prelude::*;
+use crate::framebuffer::Framebuffer;
+use crate::uniforms::Uniforms;
+use crate::math::{smoothstep, Quat, Vec3};
+use crate::noise::{default_generator, perlin_noise, Basis, Fractal};
+use crate::blackbody::{linear_to_srgb, srgb_to_linear};
+use crate::corona::screen_circle;
+
+/// Parámetros de la cáscara volumétrica (distancias en radios estelares)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VolumetricShell {
+    pub thickness: f32,    // Altura máxima sobre la superficie
+    pub steps: u32,        // Muestras por rayo
+    pub density: f32,      // Escala de la densidad
+    pub emission: Vec3,    // Color emitido por unidad de densidad (RGB lineal, Hα rojizo)
+    pub absorption: f32,   // Extinción por unidad de densidad
+    pub scale_height: f32, // Altura a la que la densidad cae a 1/e
+    pub noise_scale: f32,  // Frecuencia de los filamentos
+    pub speed: f32,        // Velocidad de evolución del campo
+    pub ridges: Fractal,   // Ruido de los filamentos (se construye una vez, no por muestra)
+}
+
+impl Default for VolumetricShell {
+    fn default() -> Self {
+        Self {
+            thickness: 0.4,
+            steps: 48,
+            density: 20.0,
+            emission: Vec3::new(1.0, 0.28, 0.32),
+            absorption: 2.5,
+            scale_height: 0.12,
+            noise_scale: 2.0,
+            speed: 0.05,
+            ridges: Fractal::new().basis(Basis::Simplex).octaves(3),
+        }
+    }
+}
+
+/// Intersección de un rayo (dirección normalizada) con una esfera: (entrada, salida)
+fn intersect_sphere(origin: Vec3, direction: Vec3, center: Vec3, radius: f32) -> Option<(f32, f32)> {
+    let offset = origin - center;
+    let b = offset.dot(direction);
+    let c = offset.length_squared() - radius * radius;
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    Some((-b - root, -b + root))
+}
+
+/// Ruido por píxel para desfasar el inicio de cada rayo (cambia bandas por grano fino)
+fn interleaved_gradient_noise(x: i32, y: i32) -> f32 {
+    let fract = |v: f32| v - v.floor();
+    fract(52.982_918 * fract(0.067_110_56 * x as f32 + 0.005_837_15 * y as f32))
+}
+
+impl VolumetricShell {
+    /// Densidad en un punto del espacio de la estrella (radio 1 = superficie)
+    /// El campo evoluciona con `uniforms.time` (y vuelve a empezar si hay bucle)
+    pub fn density_at(&self, position: Vec3, uniforms: &Uniforms) -> f32 {
+        let radius = position.length();
+        let height = radius - 1.0;
+        if height <= 0.0 || height >= self.thickness {
+            return 0.0;
+        }
+
+        // Regiones activas: máscara de baja frecuencia sobre la dirección
+        let direction = position / radius;
+        let (drift_x, drift_z) = uniforms.noise_time(0.01);
+        let active = perlin_noise(direction.x * 1.7 + drift_x, direction.y * 1.7, direction.z * 1.7 + drift_z);
+        let mask = smoothstep(0.55, 0.7, active);
+        if mask <= 0.0 {
+            return 0.0;
+        }
+
+        // Lazos y filamentos: crestas del ruido ridged multifractal (láminas finas donde
+        // el ruido cruza 0, con el detalle concentrado sobre ellas), que al cortarse con
+        // la cáscara se ven como arcos
+        let (drift_x, drift_z) = uniforms.noise_time(self.speed);
+        let p = position * self.noise_scale + Vec3::new(drift_x, 0.0, drift_z);
+        let ridges = self.ridges.ridged(default_generator(), p);
+        let filaments = smoothstep(0.7, 0.9, ridges);
+
+        // Fade suave en el borde exterior de la cáscara
+        let top = 1.0 - smoothstep(self.thickness * 0.7, self.thickness, height);
+        mask * filaments * top * (-height / self.scale_height).exp() * self.density
+    }
+
+    /// Dibuja la cáscara alrededor de la estrella descrita por `uniforms.model_matrix`
+    /// `mesh_radius` es el radio del modelo en espacio objeto
+    pub fn render(&self, framebuffer: &mut Framebuffer, uniforms: &Uniforms, mesh_radius: f32) {
+        let model = uniforms.model_matrix;
+        let Some(to_object) = model.inverse() else {
+            return;
+        };
+        let center = model.column(3).truncate();
+        let radius = mesh_radius * model.column(0).truncate().length();
+        let outer_radius = radius * (1.0 + self.thickness);
+
+        // Pantalla -> mundo: inversa de toda la cadena de transformaciones
+        let world_to_screen = uniforms.viewport_matrix * uniforms.projection_matrix * uniforms.view_matrix;
+        let Some(screen_to_world) = world_to_screen.inverse() else {
+            return;
+        };
+        let Some((screen_center, screen_radius)) = screen_circle(uniforms, center, outer_radius) else {
+            return;
+        };
+
+        let forward = -uniforms.view_matrix.row(2).truncate().normalize();
+        // La superficie gira a la velocidad del ecuador: el campo gira con ella
+        let spin = Quat::from_axis_angle(Vec3::Y, -uniforms.differential_rotation.a * uniforms.time);
+        let steps = self.steps.max(1);
+
+        let min_x = ((screen_center.x - screen_radius).floor() as i32).max(0);
+        let max_x = ((screen_center.x + screen_radius).ceil() as i32).min(framebuffer.width as i32 - 1);
+        let min_y = ((screen_center.y - screen_radius).floor() as i32).max(0);
+        let max_y = ((screen_center.y + screen_radius).ceil() as i32).min(framebuffer.height as i32 - 1);
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                // Rayo del píxel: dos puntos a distintas profundidades definen la dirección
+                let near = screen_to_world.transform_point(Vec3::new(x as f32, y as f32, 0.25));
+                let far = screen_to_world.transform_point(Vec3::new(x as f32, y as f32, 0.75));
+                let mut direction = (far - near).normalize();
+                if direction.dot(forward) < 0.0 {
+                    direction = -direction;
+                }
+                let origin = near - direction * (near - uniforms.camera_position).dot(direction);
+
+                let Some((enter, exit)) = intersect_sphere(origin, direction, center, outer_radius) else {
+                    continue;
+                };
+                let start = enter.max(0.0);
+                let mut end = exit;
+
+                // El rayo termina en la superficie ya dibujada (buffer de profundidad)
+                if !framebuffer.is_background(x, y) {
+                    let surface = screen_to_world.transform_point(Vec3::new(x as f32, y as f32, framebuffer.depth_at(x, y)));
+                    end = end.min((surface - origin).dot(direction));
+                }
+                if end <= start {
+                    continue;
+                }
+
+                // Integración de emisión y absorción de adelante hacia atrás
+                let step = (end - start) / steps as f32;
+                let step_in_radii = step / radius;
+                let jitter = interleaved_gradient_noise(x, y);
+                let mut transmittance = 1.0;
+                let mut light = Vec3::ZERO;
+
+                for i in 0..steps {
+                    let t = start + (i as f32 + jitter) * step;
+                    let world = origin + direction * t;
+                    let local = spin.rotate(to_object.transform_point(world) / mesh_radius);
+
+                    let density = self.density_at(local, uniforms);
+                    if density <= 0.0 {
+                        continue;
+                    }
+
+                    light += self.emission * (density * step_in_radii * transmittance);
+                    transmittance *= (-self.absorption * density * step_in_radii).exp();
+                    if transmittance < 0.01 {
+                        break;
+                    }
+                }
+
+                if transmittance > 0.999 && light.length_squared() < 1e-8 {
+                    continue;
+                }
+
+                // Composición en espacio lineal sobre lo que ya había en el píxel
+                let index = (y as u32 * framebuffer.width + x as u32) as usize;
+                let pixel = framebuffer.pixels[index];
+                let blend = |channel: u8, emitted: f32| {
+                    let background = srgb_to_linear(channel as f32 / 255.0);
+                    (linear_to_srgb(background * transmittance + emitted) * 255.0) as u8
+                };
+                framebuffer.pixels[index] = Color::new(
+                    blend(pixel.r, light.x),
+                    blend(pixel.g, light.y),
+                    blend(pixel.b, light.z),
+                    pixel.a,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn density_lives_only_inside_the_shell() {
+        let shell = VolumetricShell::default();
+        let mut uniforms = Uniforms::new();
+        uniforms.time = 1.0;
+        for i in 0..200 {
+            let t = i as f32 * 0.61;
+            let direction = Vec3::new(t.sin(), (t * 1.7).cos(), (t * 0.3).sin()).normalize();
+            assert_eq!(shell.density_at(direction * 0.9, &uniforms), 0.0);
+            assert_eq!(shell.density_at(direction * (1.0 + shell.thickness + 0.01), &uniforms), 0.0);
+            assert!(shell.density_at(direction * 1.1, &uniforms) >= 0.0);
+        }
+    }
+
+    #[test]
+    fn ray_sphere_intersection() {
+        let (enter, exit) = intersect_sphere(Vec3::new(0.0, 0.0, 5.0), -Vec3::Z, Vec3::ZERO, 1.0).unwrap();
+        assert!((enter - 4.0).abs() < 1e-5 && (exit - 6.0).abs() < 1e-5);
+        assert!(intersect_sphere(Vec3::new(2.0, 0.0, 5.0), -Vec3::Z, Vec3::ZERO, 1.0).is_none());
+    }
+}